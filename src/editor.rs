@@ -8,15 +8,17 @@ use nih_plug_vizia::{assets, create_vizia_editor, ViziaState, ViziaTheming};
 use std::env;
 use std::sync::{atomic::Ordering::Relaxed, Arc};
 
+use crate::config;
+use crate::envelope::{self, follower::EnvelopeMode};
 use crate::gui::analyzer::{SpectrumAnalyzer, SpectrumBuffer};
 use crate::gui::debug::DebugContainer;
+use crate::gui::fader::FaderContainer;
 use crate::gui::knob::KnobContainer;
-use crate::params::{NoiseParams, NoiseType};
-use crate::{config, envelope};
+use crate::params::{NoiseParams, NoiseType, WhiteDistribution, LAYER_COUNT};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
-const PLUGIN_WIDTH: f32 = 400.0;
-const PLUGIN_HEIGHT: f32 = 550.0;
+const PLUGIN_WIDTH: f32 = 600.0;
+const PLUGIN_HEIGHT: f32 = 1110.0;
 const POINT_SCALE: f32 = 0.75;
 const ICON_DOWN_OPEN: &str = "\u{25BC}";
 
@@ -25,21 +27,73 @@ struct UiData {
     pub gui_context: Arc<dyn GuiContext>,
     params: Arc<NoiseParams>,
     noise_types: Vec<String>,
-    white_noise_types: Vec<String>,
     envelope_mode_types: Vec<String>,
     debug: config::Debug,
     sample_rate: Arc<AtomicF32>,
+    input_level: Arc<AtomicF32>,
     spectrum_buffer: SpectrumBuffer,
 }
 
 #[derive(Debug)]
 pub enum ParamChangeEvent {
     NoiseEvent(String),
+    WhiteDistributionSet(f32),
+    WhiteSigmaSet(f32),
+    WhiteClipSet(f32),
     MixSet(f32),
     GainSet(f32),
     LpfSet(f32),
     HpfSet(f32),
+    LpfQSet(f32),
+    HpfQSet(f32),
+    FilterSlopeSet(f32),
+    ToneTypeSet(f32),
+    ToneFrequencySet(f32),
+    ToneGainSet(f32),
+    ToneQSet(f32),
+    FilterResponseSet(f32),
+    FilterEngineSet(f32),
+    FilterLinkToggle,
+    FilterCenterSet(f32),
+    FilterWidthSet(f32),
+    SlopeSet(f32),
+    VelvetDensitySet(f32),
+    CrackleDensitySet(f32),
+    CrackleSizeSet(f32),
+    CrackleHissSet(f32),
+    HumFrequencySet(f32),
+    HumHarmonicsSet(f32),
+    HumBuzzSet(f32),
+    HumDriftSet(f32),
+    TapeSpeedSet(f32),
+    TapeModulationSet(f32),
+    LayerSet(usize, f32),
+    StereoModeSet(f32),
+    StereoWidthSet(f32),
     EnvelopeModeEvent(String),
+    EnvelopeSourceSet(f32),
+    LinkEnvelopesToggle,
+    EnvelopeDepthSet(f32),
+    LookaheadSet(f32),
+    EnvelopeCurveSet(f32),
+    EnvelopeFloorSet(f32),
+    EnvelopeGainSet(f32),
+    DuckDepthSet(f32),
+    DuckThresholdSet(f32),
+    DuckRangeSet(f32),
+    VocoderBandsSet(f32),
+    VocoderLowSet(f32),
+    VocoderHighSet(f32),
+    GateTriggerSet(f32),
+    GateThresholdSet(f32),
+    GateAttackSet(f32),
+    GateHoldSet(f32),
+    GateDecaySet(f32),
+    EnvelopeAttackSet(f32),
+    EnvelopeHoldSet(f32),
+    EnvelopeReleaseSet(f32),
+    EnvelopeDetectorSet(f32),
+    EnvelopeRmsWindowSet(f32),
 }
 
 impl Model for UiData {
@@ -63,8 +117,55 @@ impl Model for UiData {
                     setter.begin_set_parameter(&self.params.noise_type);
                     setter.set_parameter(&self.params.noise_type, NoiseType::Violet);
                     setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "slope" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Slope);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "blue" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Blue);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "grey" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Grey);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "velvet" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Velvet);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "crackle" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Crackle);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "hum" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Hum);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "tape" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Tape);
+                    setter.end_set_parameter(&self.params.noise_type);
+                } else if s == "layers" {
+                    setter.begin_set_parameter(&self.params.noise_type);
+                    setter.set_parameter(&self.params.noise_type, NoiseType::Layers);
+                    setter.end_set_parameter(&self.params.noise_type);
                 }
             }
+            ParamChangeEvent::WhiteDistributionSet(f) => {
+                setter.begin_set_parameter(&self.params.white_dist);
+                setter.set_parameter_normalized(&self.params.white_dist, *f);
+                setter.end_set_parameter(&self.params.white_dist);
+            }
+            ParamChangeEvent::WhiteSigmaSet(f) => {
+                setter.begin_set_parameter(&self.params.white_sigma);
+                setter.set_parameter_normalized(&self.params.white_sigma, *f);
+                setter.end_set_parameter(&self.params.white_sigma);
+            }
+            ParamChangeEvent::WhiteClipSet(f) => {
+                setter.begin_set_parameter(&self.params.white_clip);
+                setter.set_parameter_normalized(&self.params.white_clip, *f);
+                setter.end_set_parameter(&self.params.white_clip);
+            }
             ParamChangeEvent::MixSet(f) => {
                 setter.begin_set_parameter(&self.params.mix);
                 setter.set_parameter(&self.params.mix, *f);
@@ -85,6 +186,255 @@ impl Model for UiData {
                 setter.set_parameter_normalized(&self.params.hpf_fc, *f);
                 setter.end_set_parameter(&self.params.hpf_fc);
             }
+            ParamChangeEvent::LpfQSet(f) => {
+                setter.begin_set_parameter(&self.params.lpf_q);
+                setter.set_parameter_normalized(&self.params.lpf_q, *f);
+                setter.end_set_parameter(&self.params.lpf_q);
+            }
+            ParamChangeEvent::HpfQSet(f) => {
+                setter.begin_set_parameter(&self.params.hpf_q);
+                setter.set_parameter_normalized(&self.params.hpf_q, *f);
+                setter.end_set_parameter(&self.params.hpf_q);
+            }
+            ParamChangeEvent::ToneTypeSet(f) => {
+                setter.begin_set_parameter(&self.params.tone_type);
+                setter.set_parameter_normalized(&self.params.tone_type, *f);
+                setter.end_set_parameter(&self.params.tone_type);
+            }
+            ParamChangeEvent::ToneFrequencySet(f) => {
+                setter.begin_set_parameter(&self.params.tone_fc);
+                setter.set_parameter_normalized(&self.params.tone_fc, *f);
+                setter.end_set_parameter(&self.params.tone_fc);
+            }
+            ParamChangeEvent::ToneGainSet(f) => {
+                setter.begin_set_parameter(&self.params.tone_gain);
+                setter.set_parameter_normalized(&self.params.tone_gain, *f);
+                setter.end_set_parameter(&self.params.tone_gain);
+            }
+            ParamChangeEvent::ToneQSet(f) => {
+                setter.begin_set_parameter(&self.params.tone_q);
+                setter.set_parameter_normalized(&self.params.tone_q, *f);
+                setter.end_set_parameter(&self.params.tone_q);
+            }
+            ParamChangeEvent::FilterSlopeSet(f) => {
+                setter.begin_set_parameter(&self.params.filter_slope);
+                setter.set_parameter_normalized(&self.params.filter_slope, *f);
+                setter.end_set_parameter(&self.params.filter_slope);
+            }
+            ParamChangeEvent::FilterResponseSet(f) => {
+                setter.begin_set_parameter(&self.params.filter_response);
+                setter.set_parameter_normalized(&self.params.filter_response, *f);
+                setter.end_set_parameter(&self.params.filter_response);
+            }
+            ParamChangeEvent::FilterEngineSet(f) => {
+                setter.begin_set_parameter(&self.params.filter_engine);
+                setter.set_parameter_normalized(&self.params.filter_engine, *f);
+                setter.end_set_parameter(&self.params.filter_engine);
+            }
+            ParamChangeEvent::FilterLinkToggle => {
+                setter.begin_set_parameter(&self.params.filter_link);
+                setter.set_parameter(&self.params.filter_link, !self.params.filter_link.value());
+                setter.end_set_parameter(&self.params.filter_link);
+            }
+            ParamChangeEvent::FilterCenterSet(f) => {
+                setter.begin_set_parameter(&self.params.filter_center);
+                setter.set_parameter_normalized(&self.params.filter_center, *f);
+                setter.end_set_parameter(&self.params.filter_center);
+            }
+            ParamChangeEvent::FilterWidthSet(f) => {
+                setter.begin_set_parameter(&self.params.filter_width);
+                setter.set_parameter_normalized(&self.params.filter_width, *f);
+                setter.end_set_parameter(&self.params.filter_width);
+            }
+            ParamChangeEvent::SlopeSet(f) => {
+                setter.begin_set_parameter(&self.params.noise_slope);
+                setter.set_parameter_normalized(&self.params.noise_slope, *f);
+                setter.end_set_parameter(&self.params.noise_slope);
+            }
+            ParamChangeEvent::VelvetDensitySet(f) => {
+                setter.begin_set_parameter(&self.params.velvet_density);
+                setter.set_parameter_normalized(&self.params.velvet_density, *f);
+                setter.end_set_parameter(&self.params.velvet_density);
+            }
+            ParamChangeEvent::CrackleDensitySet(f) => {
+                setter.begin_set_parameter(&self.params.crackle_density);
+                setter.set_parameter_normalized(&self.params.crackle_density, *f);
+                setter.end_set_parameter(&self.params.crackle_density);
+            }
+            ParamChangeEvent::CrackleSizeSet(f) => {
+                setter.begin_set_parameter(&self.params.crackle_size);
+                setter.set_parameter_normalized(&self.params.crackle_size, *f);
+                setter.end_set_parameter(&self.params.crackle_size);
+            }
+            ParamChangeEvent::CrackleHissSet(f) => {
+                setter.begin_set_parameter(&self.params.crackle_hiss);
+                setter.set_parameter_normalized(&self.params.crackle_hiss, *f);
+                setter.end_set_parameter(&self.params.crackle_hiss);
+            }
+            ParamChangeEvent::HumFrequencySet(f) => {
+                setter.begin_set_parameter(&self.params.hum_frequency);
+                setter.set_parameter_normalized(&self.params.hum_frequency, *f);
+                setter.end_set_parameter(&self.params.hum_frequency);
+            }
+            ParamChangeEvent::HumHarmonicsSet(f) => {
+                setter.begin_set_parameter(&self.params.hum_harmonics);
+                setter.set_parameter_normalized(&self.params.hum_harmonics, *f);
+                setter.end_set_parameter(&self.params.hum_harmonics);
+            }
+            ParamChangeEvent::HumBuzzSet(f) => {
+                setter.begin_set_parameter(&self.params.hum_buzz);
+                setter.set_parameter_normalized(&self.params.hum_buzz, *f);
+                setter.end_set_parameter(&self.params.hum_buzz);
+            }
+            ParamChangeEvent::HumDriftSet(f) => {
+                setter.begin_set_parameter(&self.params.hum_drift);
+                setter.set_parameter_normalized(&self.params.hum_drift, *f);
+                setter.end_set_parameter(&self.params.hum_drift);
+            }
+            ParamChangeEvent::TapeSpeedSet(f) => {
+                setter.begin_set_parameter(&self.params.tape_speed);
+                setter.set_parameter_normalized(&self.params.tape_speed, *f);
+                setter.end_set_parameter(&self.params.tape_speed);
+            }
+            ParamChangeEvent::TapeModulationSet(f) => {
+                setter.begin_set_parameter(&self.params.tape_modulation);
+                setter.set_parameter_normalized(&self.params.tape_modulation, *f);
+                setter.end_set_parameter(&self.params.tape_modulation);
+            }
+            ParamChangeEvent::LayerSet(idx, f) => {
+                let (_, level) = self.params.layers.levels()[*idx];
+                setter.begin_set_parameter(level);
+                setter.set_parameter_normalized(level, *f);
+                setter.end_set_parameter(level);
+            }
+            ParamChangeEvent::StereoModeSet(f) => {
+                setter.begin_set_parameter(&self.params.stereo_mode);
+                setter.set_parameter_normalized(&self.params.stereo_mode, *f);
+                setter.end_set_parameter(&self.params.stereo_mode);
+            }
+            ParamChangeEvent::StereoWidthSet(f) => {
+                setter.begin_set_parameter(&self.params.stereo_width);
+                setter.set_parameter_normalized(&self.params.stereo_width, *f);
+                setter.end_set_parameter(&self.params.stereo_width);
+            }
+            ParamChangeEvent::EnvelopeSourceSet(f) => {
+                setter.begin_set_parameter(&self.params.env_source);
+                setter.set_parameter_normalized(&self.params.env_source, *f);
+                setter.end_set_parameter(&self.params.env_source);
+            }
+            ParamChangeEvent::LinkEnvelopesToggle => {
+                setter.begin_set_parameter(&self.params.link_envelopes);
+                setter.set_parameter(
+                    &self.params.link_envelopes,
+                    !self.params.link_envelopes.value(),
+                );
+                setter.end_set_parameter(&self.params.link_envelopes);
+            }
+            ParamChangeEvent::EnvelopeDepthSet(f) => {
+                setter.begin_set_parameter(&self.params.env_depth);
+                setter.set_parameter_normalized(&self.params.env_depth, *f);
+                setter.end_set_parameter(&self.params.env_depth);
+            }
+            ParamChangeEvent::EnvelopeCurveSet(f) => {
+                setter.begin_set_parameter(&self.params.env_curve);
+                setter.set_parameter_normalized(&self.params.env_curve, *f);
+                setter.end_set_parameter(&self.params.env_curve);
+            }
+            ParamChangeEvent::EnvelopeFloorSet(f) => {
+                setter.begin_set_parameter(&self.params.env_floor);
+                setter.set_parameter_normalized(&self.params.env_floor, *f);
+                setter.end_set_parameter(&self.params.env_floor);
+            }
+            ParamChangeEvent::EnvelopeGainSet(f) => {
+                setter.begin_set_parameter(&self.params.env_gain);
+                setter.set_parameter_normalized(&self.params.env_gain, *f);
+                setter.end_set_parameter(&self.params.env_gain);
+            }
+            ParamChangeEvent::LookaheadSet(f) => {
+                setter.begin_set_parameter(&self.params.lookahead);
+                setter.set_parameter_normalized(&self.params.lookahead, *f);
+                setter.end_set_parameter(&self.params.lookahead);
+            }
+            ParamChangeEvent::DuckDepthSet(f) => {
+                setter.begin_set_parameter(&self.params.duck_depth);
+                setter.set_parameter_normalized(&self.params.duck_depth, *f);
+                setter.end_set_parameter(&self.params.duck_depth);
+            }
+            ParamChangeEvent::DuckThresholdSet(f) => {
+                setter.begin_set_parameter(&self.params.duck_threshold);
+                setter.set_parameter_normalized(&self.params.duck_threshold, *f);
+                setter.end_set_parameter(&self.params.duck_threshold);
+            }
+            ParamChangeEvent::DuckRangeSet(f) => {
+                setter.begin_set_parameter(&self.params.duck_range);
+                setter.set_parameter_normalized(&self.params.duck_range, *f);
+                setter.end_set_parameter(&self.params.duck_range);
+            }
+            ParamChangeEvent::VocoderBandsSet(f) => {
+                setter.begin_set_parameter(&self.params.vocoder_bands);
+                setter.set_parameter_normalized(&self.params.vocoder_bands, *f);
+                setter.end_set_parameter(&self.params.vocoder_bands);
+            }
+            ParamChangeEvent::VocoderLowSet(f) => {
+                setter.begin_set_parameter(&self.params.vocoder_low);
+                setter.set_parameter_normalized(&self.params.vocoder_low, *f);
+                setter.end_set_parameter(&self.params.vocoder_low);
+            }
+            ParamChangeEvent::VocoderHighSet(f) => {
+                setter.begin_set_parameter(&self.params.vocoder_high);
+                setter.set_parameter_normalized(&self.params.vocoder_high, *f);
+                setter.end_set_parameter(&self.params.vocoder_high);
+            }
+            ParamChangeEvent::GateTriggerSet(f) => {
+                setter.begin_set_parameter(&self.params.gate_trigger);
+                setter.set_parameter_normalized(&self.params.gate_trigger, *f);
+                setter.end_set_parameter(&self.params.gate_trigger);
+            }
+            ParamChangeEvent::GateThresholdSet(f) => {
+                setter.begin_set_parameter(&self.params.gate_threshold);
+                setter.set_parameter_normalized(&self.params.gate_threshold, *f);
+                setter.end_set_parameter(&self.params.gate_threshold);
+            }
+            ParamChangeEvent::GateAttackSet(f) => {
+                setter.begin_set_parameter(&self.params.gate_attack);
+                setter.set_parameter_normalized(&self.params.gate_attack, *f);
+                setter.end_set_parameter(&self.params.gate_attack);
+            }
+            ParamChangeEvent::GateHoldSet(f) => {
+                setter.begin_set_parameter(&self.params.gate_hold);
+                setter.set_parameter_normalized(&self.params.gate_hold, *f);
+                setter.end_set_parameter(&self.params.gate_hold);
+            }
+            ParamChangeEvent::GateDecaySet(f) => {
+                setter.begin_set_parameter(&self.params.gate_decay);
+                setter.set_parameter_normalized(&self.params.gate_decay, *f);
+                setter.end_set_parameter(&self.params.gate_decay);
+            }
+            ParamChangeEvent::EnvelopeAttackSet(f) => {
+                setter.begin_set_parameter(&self.params.env_attack);
+                setter.set_parameter_normalized(&self.params.env_attack, *f);
+                setter.end_set_parameter(&self.params.env_attack);
+            }
+            ParamChangeEvent::EnvelopeHoldSet(f) => {
+                setter.begin_set_parameter(&self.params.env_hold);
+                setter.set_parameter_normalized(&self.params.env_hold, *f);
+                setter.end_set_parameter(&self.params.env_hold);
+            }
+            ParamChangeEvent::EnvelopeReleaseSet(f) => {
+                setter.begin_set_parameter(&self.params.env_release);
+                setter.set_parameter_normalized(&self.params.env_release, *f);
+                setter.end_set_parameter(&self.params.env_release);
+            }
+            ParamChangeEvent::EnvelopeDetectorSet(f) => {
+                setter.begin_set_parameter(&self.params.env_detector);
+                setter.set_parameter_normalized(&self.params.env_detector, *f);
+                setter.end_set_parameter(&self.params.env_detector);
+            }
+            ParamChangeEvent::EnvelopeRmsWindowSet(f) => {
+                setter.begin_set_parameter(&self.params.env_rms_window);
+                setter.set_parameter_normalized(&self.params.env_rms_window, *f);
+                setter.end_set_parameter(&self.params.env_rms_window);
+            }
             ParamChangeEvent::EnvelopeModeEvent(s) => {
                 if s == "follow" {
                     setter.begin_set_parameter(&self.params.env_mode);
@@ -100,6 +450,27 @@ impl Model for UiData {
                         envelope::follower::EnvelopeMode::Continuous,
                     );
                     setter.end_set_parameter(&self.params.env_mode);
+                } else if s == "duck" {
+                    setter.begin_set_parameter(&self.params.env_mode);
+                    setter.set_parameter(
+                        &self.params.env_mode,
+                        envelope::follower::EnvelopeMode::Duck,
+                    );
+                    setter.end_set_parameter(&self.params.env_mode);
+                } else if s == "gate" {
+                    setter.begin_set_parameter(&self.params.env_mode);
+                    setter.set_parameter(
+                        &self.params.env_mode,
+                        envelope::follower::EnvelopeMode::Gate,
+                    );
+                    setter.end_set_parameter(&self.params.env_mode);
+                } else if s == "vocoder" {
+                    setter.begin_set_parameter(&self.params.env_mode);
+                    setter.set_parameter(
+                        &self.params.env_mode,
+                        envelope::follower::EnvelopeMode::Vocoder,
+                    );
+                    setter.end_set_parameter(&self.params.env_mode);
                 }
             }
         });
@@ -115,6 +486,7 @@ pub(crate) fn create(
     editor_state: Arc<ViziaState>,
     debug: config::Debug,
     sample_rate: Arc<AtomicF32>,
+    input_level: Arc<AtomicF32>,
     spectrum_buffer: SpectrumBuffer,
 ) -> Option<Box<dyn Editor>> {
     create_vizia_editor(editor_state, ViziaTheming::Custom, move |cx, context| {
@@ -130,10 +502,24 @@ pub(crate) fn create(
                 "pink".to_string(),
                 "brown".to_string(),
                 "violet".to_string(),
+                "slope".to_string(),
+                "blue".to_string(),
+                "grey".to_string(),
+                "velvet".to_string(),
+                "crackle".to_string(),
+                "hum".to_string(),
+                "tape".to_string(),
+                "layers".to_string(),
+            ],
+            envelope_mode_types: vec![
+                "follow".to_string(),
+                "continuous".to_string(),
+                "duck".to_string(),
+                "gate".to_string(),
+                "vocoder".to_string(),
             ],
-            white_noise_types: vec!["normal".to_string(), "uniform".to_string()],
-            envelope_mode_types: vec!["follow".to_string(), "continuous".to_string()],
             sample_rate: sample_rate.clone(),
+            input_level: input_level.clone(),
             spectrum_buffer: spectrum_buffer.clone(),
         }
         .build(cx);
@@ -155,6 +541,14 @@ fn change_plugin_color(noise_color: &str) -> Color {
         "pink" => Color::from("#FFC0CB"),
         "brown" => Color::from("#C19A6B"),
         "violet" => Color::from("#CF9FFF"),
+        "slope" => Color::from("#E3B5A4"),
+        "blue" => Color::from("#A7C7E7"),
+        "grey" => Color::from("#C8C8C8"),
+        "velvet" => Color::from("#C08497"),
+        "crackle" => Color::from("#D4B483"),
+        "hum" => Color::from("#B5C99A"),
+        "tape" => Color::from("#B8A99A"),
+        "layers" => Color::from("#E6DCCB"),
         _ => Color::from("#F9F6EE"),
     };
 
@@ -223,6 +617,601 @@ fn create_hpf_block(cx: &mut Context) -> Handle<KnobContainer> {
     )
 }
 
+fn create_lpf_q_block(cx: &mut Context) -> Handle<KnobContainer> {
+    KnobContainer::new(
+        cx,
+        "LPF Q".to_string(),
+        UiData::params.map(|p| p.lpf_q.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.lpf_q.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::LpfQSet(val));
+        },
+    )
+}
+
+fn create_hpf_q_block(cx: &mut Context) -> Handle<KnobContainer> {
+    KnobContainer::new(
+        cx,
+        "HPF Q".to_string(),
+        UiData::params.map(|p| p.hpf_q.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.hpf_q.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::HpfQSet(val));
+        },
+    )
+}
+
+fn create_tone_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        create_param_dropdown(cx, "Tone", |p| &p.tone_type, ParamChangeEvent::ToneTypeSet);
+        KnobContainer::new(
+            cx,
+            "Tone Freq".to_string(),
+            UiData::params.map(|p| p.tone_fc.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.tone_fc.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::ToneFrequencySet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Tone Gain".to_string(),
+            UiData::params.map(|p| p.tone_gain.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.tone_gain.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::ToneGainSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Tone Q".to_string(),
+            UiData::params.map(|p| p.tone_q.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.tone_q.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::ToneQSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// Steepness, response and engine shared by the LPF and HPF, and the band
+/// they form when linked
+fn create_filter_controls_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        create_param_dropdown(
+            cx,
+            "Filter Slope",
+            |p| &p.filter_slope,
+            ParamChangeEvent::FilterSlopeSet,
+        );
+        create_param_dropdown(
+            cx,
+            "Response",
+            |p| &p.filter_response,
+            ParamChangeEvent::FilterResponseSet,
+        );
+        create_param_dropdown(
+            cx,
+            "Engine",
+            |p| &p.filter_engine,
+            ParamChangeEvent::FilterEngineSet,
+        );
+        create_filter_link_toggle(cx);
+        KnobContainer::new(
+            cx,
+            "Center".to_string(),
+            UiData::params.map(|p| p.filter_center.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.filter_center.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::FilterCenterSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Width".to_string(),
+            UiData::params.map(|p| p.filter_width.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.filter_width.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::FilterWidthSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// Turns linking the LPF and HPF into a band on and off
+fn create_filter_link_toggle(cx: &mut Context) -> Handle<VStack> {
+    VStack::new(cx, |cx| {
+        Label::new(cx, "Link");
+        Checkbox::new(cx, UiData::params.map(|p| p.filter_link.value()))
+            .on_toggle(|cx| cx.emit(ParamChangeEvent::FilterLinkToggle));
+    })
+    .child_space(Stretch(1.0))
+}
+
+fn create_slope_block(cx: &mut Context) -> Handle<KnobContainer> {
+    KnobContainer::new(
+        cx,
+        "Slope".to_string(),
+        UiData::params.map(|p| p.noise_slope.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.noise_slope.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::SlopeSet(val));
+        },
+    )
+}
+
+fn create_velvet_density_block(cx: &mut Context) -> Handle<KnobContainer> {
+    KnobContainer::new(
+        cx,
+        "Density".to_string(),
+        UiData::params.map(|p| p.velvet_density.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.velvet_density.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::VelvetDensitySet(val));
+        },
+    )
+}
+
+fn create_crackle_blocks(cx: &mut Context) {
+    KnobContainer::new(
+        cx,
+        "Density".to_string(),
+        UiData::params.map(|p| p.crackle_density.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.crackle_density.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::CrackleDensitySet(val));
+        },
+    );
+    KnobContainer::new(
+        cx,
+        "Size".to_string(),
+        UiData::params.map(|p| p.crackle_size.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.crackle_size.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::CrackleSizeSet(val));
+        },
+    );
+    KnobContainer::new(
+        cx,
+        "Hiss".to_string(),
+        UiData::params.map(|p| p.crackle_hiss.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.crackle_hiss.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::CrackleHissSet(val));
+        },
+    );
+}
+
+fn create_hum_blocks(cx: &mut Context) {
+    create_param_dropdown(
+        cx,
+        "Mains",
+        |p| &p.hum_frequency,
+        ParamChangeEvent::HumFrequencySet,
+    );
+    KnobContainer::new(
+        cx,
+        "Harmonics".to_string(),
+        UiData::params.map(|p| p.hum_harmonics.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.hum_harmonics.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::HumHarmonicsSet(val));
+        },
+    );
+    KnobContainer::new(
+        cx,
+        "Buzz".to_string(),
+        UiData::params.map(|p| p.hum_buzz.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.hum_buzz.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::HumBuzzSet(val));
+        },
+    );
+    KnobContainer::new(
+        cx,
+        "Drift".to_string(),
+        UiData::params.map(|p| p.hum_drift.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.hum_drift.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::HumDriftSet(val));
+        },
+    );
+}
+
+fn create_tape_blocks(cx: &mut Context) {
+    create_param_dropdown(
+        cx,
+        "Speed",
+        |p| &p.tape_speed,
+        ParamChangeEvent::TapeSpeedSet,
+    );
+    KnobContainer::new(
+        cx,
+        "Modulation".to_string(),
+        UiData::params.map(|p| p.tape_modulation.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.tape_modulation.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::TapeModulationSet(val));
+        },
+    );
+}
+
+/// One fader per noise type, labelled with the name of its layer
+fn create_layer_faders(cx: &mut Context) {
+    for idx in 0..LAYER_COUNT {
+        FaderContainer::new(
+            cx,
+            format!("{:?}", UiData::params.get(cx).layers.levels()[idx].0),
+            UiData::params.map(move |p| p.layers.levels()[idx].1.unmodulated_normalized_value()),
+            UiData::params.map(move |p| p.layers.levels()[idx].1.to_string()),
+            move |cx, val| {
+                cx.emit(ParamChangeEvent::LayerSet(idx, val));
+            },
+        );
+    }
+}
+
+/// The distribution of the white noise every noise type is built from. The
+/// sigma and clip knobs only show for the distributions that use them.
+fn create_white_noise_row(cx: &mut Context) {
+    Binding::new(
+        cx,
+        UiData::params.map(|p| p.white_dist.to_string()),
+        |cx, _| {
+            let white_dist = UiData::params.get(cx).white_dist.value();
+            HStack::new(cx, move |cx| {
+                create_param_dropdown(
+                    cx,
+                    "White Distribution",
+                    |p| &p.white_dist,
+                    ParamChangeEvent::WhiteDistributionSet,
+                );
+                if matches!(
+                    white_dist,
+                    WhiteDistribution::Gaussian | WhiteDistribution::Laplacian
+                ) {
+                    KnobContainer::new(
+                        cx,
+                        "Sigma".to_string(),
+                        UiData::params.map(|p| p.white_sigma.unmodulated_normalized_value()),
+                        UiData::params.map(|p| p.white_sigma.to_string()),
+                        |cx, val| {
+                            cx.emit(ParamChangeEvent::WhiteSigmaSet(val));
+                        },
+                    );
+                    KnobContainer::new(
+                        cx,
+                        "Clip".to_string(),
+                        UiData::params.map(|p| p.white_clip.unmodulated_normalized_value()),
+                        UiData::params.map(|p| p.white_clip.to_string()),
+                        |cx, val| {
+                            cx.emit(ParamChangeEvent::WhiteClipSet(val));
+                        },
+                    );
+                }
+            })
+            .class("knob-container");
+        },
+    );
+}
+
+/// Whether every channel gets the same noise, and how wide decorrelated
+/// noise is spread
+fn create_stereo_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        create_param_dropdown(
+            cx,
+            "Stereo",
+            |p| &p.stereo_mode,
+            ParamChangeEvent::StereoModeSet,
+        );
+        KnobContainer::new(
+            cx,
+            "Stereo Width".to_string(),
+            UiData::params.map(|p| p.stereo_width.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.stereo_width.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::StereoWidthSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// Knobs for the settings that only apply to the selected noise type
+fn create_noise_controls_row(cx: &mut Context) -> Handle<HStack> {
+    let noise_type = UiData::params.get(cx).noise_type.value();
+    HStack::new(cx, move |cx| match noise_type {
+        NoiseType::Slope => {
+            create_slope_block(cx);
+        }
+        NoiseType::Velvet => {
+            create_velvet_density_block(cx);
+        }
+        NoiseType::Crackle => create_crackle_blocks(cx),
+        NoiseType::Hum => create_hum_blocks(cx),
+        NoiseType::Tape => create_tape_blocks(cx),
+        NoiseType::Layers => create_layer_faders(cx),
+        _ => {}
+    })
+    .class(if noise_type == NoiseType::Layers {
+        "fader-container"
+    } else {
+        "knob-container"
+    })
+}
+
+/// What the envelope follower listens to and how it detects the level
+fn create_envelope_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        create_param_dropdown(
+            cx,
+            "Source",
+            |p| &p.env_source,
+            ParamChangeEvent::EnvelopeSourceSet,
+        );
+        create_link_envelopes_toggle(cx);
+        create_param_dropdown(
+            cx,
+            "Detector",
+            |p| &p.env_detector,
+            ParamChangeEvent::EnvelopeDetectorSet,
+        );
+        KnobContainer::new(
+            cx,
+            "Window".to_string(),
+            UiData::params.map(|p| p.env_rms_window.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_rms_window.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeRmsWindowSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// Timing of the envelope follower, and how far it reads ahead of the dry
+/// signal
+fn create_envelope_timing_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        KnobContainer::new(
+            cx,
+            "Attack".to_string(),
+            UiData::params.map(|p| p.env_attack.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_attack.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeAttackSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Hold".to_string(),
+            UiData::params.map(|p| p.env_hold.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_hold.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeHoldSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Release".to_string(),
+            UiData::params.map(|p| p.env_release.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_release.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeReleaseSet(val));
+            },
+        );
+        create_lookahead_block(cx);
+    })
+    .class("knob-container")
+}
+
+/// Turns making every channel follow the loudest one on and off
+fn create_link_envelopes_toggle(cx: &mut Context) -> Handle<VStack> {
+    VStack::new(cx, |cx| {
+        Label::new(cx, "Link");
+        Checkbox::new(cx, UiData::params.map(|p| p.link_envelopes.value()))
+            .on_toggle(|cx| cx.emit(ParamChangeEvent::LinkEnvelopesToggle));
+    })
+    .child_space(Stretch(1.0))
+}
+
+fn create_lookahead_block(cx: &mut Context) -> Handle<KnobContainer> {
+    KnobContainer::new(
+        cx,
+        "Lookahead".to_string(),
+        UiData::params.map(|p| p.lookahead.unmodulated_normalized_value()),
+        UiData::params.map(|p| p.lookahead.to_string()),
+        |cx, val| {
+            cx.emit(ParamChangeEvent::LookaheadSet(val));
+        },
+    )
+}
+
+/// How follow mode shapes the envelope before applying it to the noise
+fn create_envelope_shape_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        KnobContainer::new(
+            cx,
+            "Env Depth".to_string(),
+            UiData::params.map(|p| p.env_depth.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_depth.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeDepthSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Curve".to_string(),
+            UiData::params.map(|p| p.env_curve.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_curve.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeCurveSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Floor".to_string(),
+            UiData::params.map(|p| p.env_floor.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_floor.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeFloorSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Env Gain".to_string(),
+            UiData::params.map(|p| p.env_gain.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.env_gain.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::EnvelopeGainSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// How far duck mode backs the noise off, and the input levels it starts
+/// and finishes ducking at
+fn create_duck_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        KnobContainer::new(
+            cx,
+            "Duck Depth".to_string(),
+            UiData::params.map(|p| p.duck_depth.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.duck_depth.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::DuckDepthSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Threshold".to_string(),
+            UiData::params.map(|p| p.duck_threshold.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.duck_threshold.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::DuckThresholdSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Range".to_string(),
+            UiData::params.map(|p| p.duck_range.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.duck_range.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::DuckRangeSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// What triggers a burst in gate mode, and the shape of the burst
+fn create_gate_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        create_param_dropdown(
+            cx,
+            "Trigger",
+            |p| &p.gate_trigger,
+            ParamChangeEvent::GateTriggerSet,
+        );
+        KnobContainer::new(
+            cx,
+            "Threshold".to_string(),
+            UiData::params.map(|p| p.gate_threshold.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.gate_threshold.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::GateThresholdSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Burst Attack".to_string(),
+            UiData::params.map(|p| p.gate_attack.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.gate_attack.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::GateAttackSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Burst Hold".to_string(),
+            UiData::params.map(|p| p.gate_hold.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.gate_hold.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::GateHoldSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Burst Decay".to_string(),
+            UiData::params.map(|p| p.gate_decay.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.gate_decay.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::GateDecaySet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// Band count and range of vocoder mode
+fn create_vocoder_row(cx: &mut Context) -> Handle<HStack> {
+    HStack::new(cx, |cx| {
+        KnobContainer::new(
+            cx,
+            "Bands".to_string(),
+            UiData::params.map(|p| p.vocoder_bands.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.vocoder_bands.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::VocoderBandsSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "Low".to_string(),
+            UiData::params.map(|p| p.vocoder_low.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.vocoder_low.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::VocoderLowSet(val));
+            },
+        );
+        KnobContainer::new(
+            cx,
+            "High".to_string(),
+            UiData::params.map(|p| p.vocoder_high.unmodulated_normalized_value()),
+            UiData::params.map(|p| p.vocoder_high.to_string()),
+            |cx, val| {
+                cx.emit(ParamChangeEvent::VocoderHighSet(val));
+            },
+        );
+    })
+    .class("knob-container")
+}
+
+/// The envelope follower rows and the row of the active envelope mode. These
+/// are rebuilt whenever the mode changes, and continuous mode shows none.
+fn create_envelope_rows(cx: &mut Context) {
+    Binding::new(
+        cx,
+        UiData::params.map(|p| p.env_mode.to_string()),
+        |cx, _| {
+            let create_mode_row = match UiData::params.get(cx).env_mode.value() {
+                EnvelopeMode::Continuous => return,
+                EnvelopeMode::Follow => create_envelope_shape_row,
+                EnvelopeMode::Duck => create_duck_row,
+                EnvelopeMode::Gate => create_gate_row,
+                EnvelopeMode::Vocoder => create_vocoder_row,
+            };
+            create_envelope_row(cx);
+            create_envelope_timing_row(cx);
+            create_mode_row(cx);
+        },
+    );
+}
+
 fn create_spectrum_analyzer(cx: &mut Context) -> Handle<HStack> {
     HStack::new(cx, |cx| {
         ZStack::new(cx, |cx| {
@@ -230,12 +1219,92 @@ fn create_spectrum_analyzer(cx: &mut Context) -> Handle<HStack> {
                 cx,
                 UiData::spectrum_buffer.get(cx),
                 UiData::sample_rate.get(cx),
+                UiData::input_level.get(cx),
+                UiData::params.get(cx),
             );
         });
     })
     .class("spectrum-analyzer-container")
 }
 
+/// Dropdown listing every value of an enum param, styled like the noise type
+/// selector. `on_select` turns the normalized value of the picked item into
+/// the param's change event.
+fn create_param_dropdown<P, F, E>(
+    cx: &mut Context,
+    label: &str,
+    param: F,
+    on_select: E,
+) -> Handle<VStack>
+where
+    P: Param,
+    F: 'static + Copy + Fn(&NoiseParams) -> &P,
+    E: 'static + Copy + Fn(f32) -> ParamChangeEvent,
+{
+    let choices: Vec<(f32, String)> = {
+        let params = UiData::params.get(cx);
+        let param = param(params.as_ref());
+        let steps = param.step_count().unwrap_or(1);
+        (0..=steps)
+            .map(|step| {
+                let normalized = step as f32 / steps as f32;
+                (
+                    normalized,
+                    param.normalized_value_to_string(normalized, false),
+                )
+            })
+            .collect()
+    };
+
+    VStack::new(cx, move |cx| {
+        Label::new(cx, label)
+            .font_size(15.0 * POINT_SCALE)
+            .class("dropdown-label");
+        Dropdown::new(
+            cx,
+            move |cx| {
+                VStack::new(cx, move |cx| {
+                    Label::new(
+                        cx,
+                        UiData::params.map(move |p| param(p.as_ref()).to_string()),
+                    );
+                    Label::new(cx, ICON_DOWN_OPEN).class("arrow");
+                })
+                .class("title")
+                .child_space(Stretch(1.0))
+            },
+            move |cx| {
+                for (normalized, name) in choices.iter().cloned() {
+                    Binding::new(
+                        cx,
+                        UiData::params
+                            .map(move |p| param(p.as_ref()).unmodulated_normalized_value()),
+                        move |cx, value| {
+                            let selected = (value.get(cx) - normalized).abs() < 1e-3;
+                            Label::new(cx, &name)
+                                .background_color(if selected {
+                                    Color::from("#c28919")
+                                } else {
+                                    Color::transparent()
+                                })
+                                .on_press(move |cx| {
+                                    cx.emit(on_select(normalized));
+                                    cx.emit(views::PopupEvent::Close);
+                                })
+                                .child_space(Stretch(1.0))
+                                .class("dropdown-label-value");
+                        },
+                    );
+                }
+            },
+        )
+        .width(Percentage(90.0))
+        .class("noise-dropdown");
+    })
+    .child_space(Stretch(1.0))
+    .class("noise-dropdown-container")
+}
+
 fn create_noise_selector(cx: &mut Context) -> Handle<VStack> {
     VStack::new(cx, |cx| {
         Label::new(cx, "Noise Type")
@@ -359,9 +1428,17 @@ fn build_gui(cx: &mut Context) -> Handle<VStack> {
             create_gain_block(cx);
             create_mix_block(cx);
             create_hpf_block(cx);
+            create_hpf_q_block(cx);
             create_lpf_block(cx);
+            create_lpf_q_block(cx);
         })
         .class("knob-container");
+        create_filter_controls_row(cx);
+        create_tone_row(cx);
+        create_noise_controls_row(cx);
+        create_white_noise_row(cx);
+        create_stereo_row(cx);
+        create_envelope_rows(cx);
         create_noise_selector_row(cx);
         if cfg!(debug_assertions) {
             HStack::new(cx, move |cx| {
@@ -1,45 +1,314 @@
 use nih_plug::prelude::Enum;
+use std::f32::consts::PI;
 use std::time::Duration;
 
+/// The longest RMS window, which sets how much history the RMS detector keeps
+pub const MAX_RMS_WINDOW: Duration = Duration::from_millis(300);
+
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+const TRUE_PEAK_TAPS: usize = 8;
+
 #[derive(Enum, PartialEq, Debug)]
 pub enum EnvelopeMode {
     Continuous,
     Follow,
+    Duck,
+    Gate,
+    Vocoder,
 }
 
+/// Which input drives the envelope follower
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum EnvelopeSource {
+    #[id = "main"]
+    Main,
+    #[id = "sidechain"]
+    Sidechain,
+}
+
+/// How the level of the incoming audio is measured before it is smoothed
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum Detector {
+    #[id = "peak"]
+    Peak,
+    #[id = "rms"]
+    #[name = "RMS"]
+    Rms,
+    #[id = "true-peak"]
+    #[name = "True Peak"]
+    TruePeak,
+}
+
+/// Smooths the level of a signal. The attack, release, hold and RMS window
+/// are kept as times, and turned into per-sample coefficients whenever the
+/// times or the sample rate change.
 pub struct EnvelopeFollower {
     envelope_value: f32,
+    sample_rate: f32,
+    attack: Duration,
+    release: Duration,
+    hold: Duration,
+    rms_window: Duration,
     attack_coefficient: f32,
     release_coefficient: f32,
+    hold_samples: usize,
+    hold_remaining: usize,
+    detector: Detector,
+    rms: Rms,
+    true_peak: TruePeak,
 }
 
 impl EnvelopeFollower {
     pub fn new(sample_rate: &f32) -> Self {
-        Self {
+        let mut follower = Self {
             envelope_value: 0.,
-            attack_coefficient: Self::calculate_coefficient(
-                &sample_rate,
-                Duration::from_millis(10),
-            ),
-            release_coefficient: Self::calculate_coefficient(
-                &sample_rate,
-                Duration::from_millis(100),
-            ),
-        }
+            sample_rate: *sample_rate,
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(100),
+            hold: Duration::ZERO,
+            rms_window: Duration::from_millis(50),
+            attack_coefficient: 0.,
+            release_coefficient: 0.,
+            hold_samples: 0,
+            hold_remaining: 0,
+            detector: Detector::Peak,
+            rms: Rms::new(*sample_rate),
+            true_peak: TruePeak::new(),
+        };
+        follower.update_coefficients();
+        follower
     }
 
     fn calculate_coefficient(sample_rate: &f32, time: Duration) -> f32 {
         (-1.0 / (sample_rate * time.as_secs_f32())).exp()
     }
 
+    fn update_coefficients(&mut self) {
+        self.attack_coefficient = Self::calculate_coefficient(&self.sample_rate, self.attack);
+        self.release_coefficient = Self::calculate_coefficient(&self.sample_rate, self.release);
+        self.hold_samples = (self.sample_rate * self.hold.as_secs_f32()) as usize;
+        self.rms.set_window(self.sample_rate, self.rms_window);
+    }
+
+    /// `rms_window` is capped at `MAX_RMS_WINDOW`
+    pub fn set_times(
+        &mut self,
+        attack: Duration,
+        release: Duration,
+        hold: Duration,
+        rms_window: Duration,
+    ) {
+        self.attack = attack;
+        self.release = release;
+        self.hold = hold;
+        self.rms_window = rms_window;
+        self.update_coefficients();
+    }
+
+    /// Re-derives the coefficients from the stored times. This reallocates
+    /// the RMS history, so it should only be called from `initialize`.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.rms = Rms::new(sample_rate);
+        }
+        self.update_coefficients();
+    }
+
+    pub fn set_detector(&mut self, detector: Detector) {
+        if detector != self.detector {
+            self.rms.reset();
+            self.true_peak.reset();
+            self.detector = detector;
+        }
+    }
+
     pub fn process(&mut self, sample: f32) -> f32 {
-        let sample_abs = sample.abs();
-        let env_coefficient = match self.envelope_value < sample_abs {
-            true => self.attack_coefficient,
-            false => self.release_coefficient,
+        let level = match self.detector {
+            Detector::Peak => sample.abs(),
+            Detector::Rms => self.rms.process(sample),
+            Detector::TruePeak => self.true_peak.process(sample),
+        };
+
+        let env_coefficient = if self.envelope_value < level {
+            self.hold_remaining = self.hold_samples;
+            self.attack_coefficient
+        } else if self.hold_remaining > 0 {
+            self.hold_remaining -= 1;
+            return self.envelope_value;
+        } else {
+            self.release_coefficient
         };
         self.envelope_value =
-            (self.envelope_value * env_coefficient) + sample_abs * (1.0 - env_coefficient);
+            (self.envelope_value * env_coefficient) + level * (1.0 - env_coefficient);
         self.envelope_value
     }
 }
+
+/// Moving RMS over a window of samples, kept as a running sum of squares
+struct Rms {
+    squares: Vec<f32>,
+    window: usize,
+    position: usize,
+    sum: f32,
+}
+
+impl Rms {
+    fn new(sample_rate: f32) -> Self {
+        let capacity = (sample_rate * MAX_RMS_WINDOW.as_secs_f32()).ceil() as usize;
+        Self {
+            squares: vec![0.0; capacity.max(1)],
+            window: 1,
+            position: 0,
+            sum: 0.0,
+        }
+    }
+
+    fn set_window(&mut self, sample_rate: f32, window: Duration) {
+        let window = ((sample_rate * window.as_secs_f32()) as usize).clamp(1, self.squares.len());
+        if window != self.window {
+            self.window = window;
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.squares.fill(0.0);
+        self.position = 0;
+        self.sum = 0.0;
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let square = sample * sample;
+        self.sum += square - self.squares[self.position];
+        self.squares[self.position] = square;
+        self.position = (self.position + 1) % self.window;
+
+        // the running sum can drift slightly below zero from rounding
+        (self.sum.max(0.0) / self.window as f32).sqrt()
+    }
+}
+
+/// Estimates the peak between samples by interpolating at 4x the sample rate,
+/// similar to the true-peak meter in ITU-R BS.1770
+struct TruePeak {
+    history: [f32; TRUE_PEAK_TAPS],
+    phases: [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING],
+}
+
+impl TruePeak {
+    fn new() -> Self {
+        // Hann windowed sinc, split into one set of taps per interpolated phase
+        let length = TRUE_PEAK_TAPS * TRUE_PEAK_OVERSAMPLING;
+        let center = (length - 1) as f32 / 2.0;
+        let mut phases = [[0.0; TRUE_PEAK_TAPS]; TRUE_PEAK_OVERSAMPLING];
+        for n in 0..length {
+            let t = (n as f32 - center) / TRUE_PEAK_OVERSAMPLING as f32;
+            let sinc = if t == 0.0 {
+                1.0
+            } else {
+                (PI * t).sin() / (PI * t)
+            };
+            let window = 0.5 - 0.5 * (2.0 * PI * (n as f32 + 0.5) / length as f32).cos();
+            phases[n % TRUE_PEAK_OVERSAMPLING][n / TRUE_PEAK_OVERSAMPLING] = sinc * window;
+        }
+
+        Self {
+            history: [0.0; TRUE_PEAK_TAPS],
+            phases,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.history = [0.0; TRUE_PEAK_TAPS];
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        self.history.rotate_right(1);
+        self.history[0] = sample;
+
+        self.phases
+            .iter()
+            .map(|taps| {
+                taps.iter()
+                    .zip(self.history.iter())
+                    .map(|(tap, sample)| tap * sample)
+                    .sum::<f32>()
+                    .abs()
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATES: [f32; 4] = [44_100.0, 48_000.0, 96_000.0, 192_000.0];
+    const ATTACK: Duration = Duration::from_millis(20);
+    const RELEASE: Duration = Duration::from_millis(200);
+    /// A one pole smoother covers 1 - 1/e of a step in one time constant
+    const ONE_TIME_CONSTANT: f32 = 0.632;
+
+    fn follower(sample_rate: f32) -> EnvelopeFollower {
+        let mut follower = EnvelopeFollower::new(&sample_rate);
+        follower.set_times(ATTACK, RELEASE, Duration::ZERO, Duration::from_millis(50));
+        follower
+    }
+
+    fn samples_in(sample_rate: f32, time: Duration) -> usize {
+        (sample_rate * time.as_secs_f32()).round() as usize
+    }
+
+    #[test]
+    fn attack_reaches_63_percent_at_attack_time() {
+        for sample_rate in SAMPLE_RATES {
+            let mut follower = follower(sample_rate);
+            let envelope = (0..samples_in(sample_rate, ATTACK))
+                .map(|_| follower.process(1.0))
+                .last()
+                .unwrap();
+
+            assert!(
+                (envelope - ONE_TIME_CONSTANT).abs() < 0.005,
+                "{envelope} after the attack time at {sample_rate} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn release_falls_63_percent_at_release_time() {
+        for sample_rate in SAMPLE_RATES {
+            let mut follower = follower(sample_rate);
+            for _ in 0..samples_in(sample_rate, ATTACK * 50) {
+                follower.process(1.0);
+            }
+            let envelope = (0..samples_in(sample_rate, RELEASE))
+                .map(|_| follower.process(0.0))
+                .last()
+                .unwrap();
+
+            assert!(
+                (1.0 - envelope - ONE_TIME_CONSTANT).abs() < 0.005,
+                "{envelope} after the release time at {sample_rate} Hz"
+            );
+        }
+    }
+
+    #[test]
+    fn sample_rate_change_keeps_times() {
+        for sample_rate in SAMPLE_RATES {
+            let mut follower = follower(44_100.0);
+            follower.set_sample_rate(sample_rate);
+            let envelope = (0..samples_in(sample_rate, ATTACK))
+                .map(|_| follower.process(1.0))
+                .last()
+                .unwrap();
+
+            assert!(
+                (envelope - ONE_TIME_CONSTANT).abs() < 0.005,
+                "{envelope} after the attack time at {sample_rate} Hz"
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use nih_plug::prelude::Enum;
+use std::time::Duration;
+
+/// How far the level has to jump above its recent average to count as a
+/// transient (6 dB)
+const TRANSIENT_RATIO: f32 = 2.0;
+/// Time constant of the recent average transients are measured against
+const TRANSIENT_AVERAGE_TIME: Duration = Duration::from_millis(50);
+/// The level has to fall this far below where the gate fired (about 3 dB)
+/// before it can fire again, so a level hovering at the threshold doesn't
+/// retrigger every sample
+const REARM_RATIO: f32 = 0.7;
+
+/// What fires a noise burst in gate mode
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum GateTrigger {
+    #[id = "threshold"]
+    Threshold,
+    #[id = "transient"]
+    Transient,
+}
+
+#[derive(PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Hold,
+    Decay,
+}
+
+/// Fires a fixed attack/hold/decay burst whenever the input level crosses the
+/// threshold or jumps like a transient. The burst always has the same shape,
+/// however long the input rings out.
+pub struct Gate {
+    sample_rate: f32,
+    attack: Duration,
+    hold: Duration,
+    decay: Duration,
+    attack_step: f32,
+    hold_samples: usize,
+    decay_step: f32,
+    stage: Stage,
+    value: f32,
+    hold_remaining: usize,
+    average: f32,
+    average_coefficient: f32,
+    armed: bool,
+}
+
+impl Gate {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut gate = Self {
+            sample_rate,
+            attack: Duration::from_millis(1),
+            hold: Duration::from_millis(50),
+            decay: Duration::from_millis(150),
+            attack_step: 0.0,
+            hold_samples: 0,
+            decay_step: 0.0,
+            stage: Stage::Idle,
+            value: 0.0,
+            hold_remaining: 0,
+            average: 0.0,
+            average_coefficient: 0.0,
+            armed: true,
+        };
+        gate.update_steps();
+        gate
+    }
+
+    fn update_steps(&mut self) {
+        // linear ramps keep the length of the burst exact
+        let samples = |time: Duration| (self.sample_rate * time.as_secs_f32()).max(1.0);
+        self.attack_step = 1.0 / samples(self.attack);
+        self.hold_samples = (self.sample_rate * self.hold.as_secs_f32()) as usize;
+        self.decay_step = 1.0 / samples(self.decay);
+        self.average_coefficient =
+            (-1.0 / (self.sample_rate * TRANSIENT_AVERAGE_TIME.as_secs_f32())).exp();
+    }
+
+    pub fn set_times(&mut self, attack: Duration, hold: Duration, decay: Duration) {
+        self.attack = attack;
+        self.hold = hold;
+        self.decay = decay;
+        self.update_steps();
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_steps();
+    }
+
+    pub fn reset(&mut self) {
+        self.stage = Stage::Idle;
+        self.value = 0.0;
+        self.average = 0.0;
+        self.armed = true;
+    }
+
+    /// Takes the input level, and returns the level of the burst
+    pub fn process(&mut self, level: f32, threshold: f32, trigger: GateTrigger) -> f32 {
+        self.average =
+            self.average * self.average_coefficient + level * (1.0 - self.average_coefficient);
+        let fire_level = match trigger {
+            GateTrigger::Threshold => threshold,
+            GateTrigger::Transient => (self.average * TRANSIENT_RATIO).max(threshold),
+        };
+
+        if self.armed && level > fire_level {
+            // a retrigger ramps up from wherever the last burst got to
+            self.stage = Stage::Attack;
+            self.armed = false;
+        } else if level < fire_level * REARM_RATIO {
+            self.armed = true;
+        }
+
+        match self.stage {
+            Stage::Idle => {}
+            Stage::Attack => {
+                self.value += self.attack_step;
+                if self.value >= 1.0 {
+                    self.value = 1.0;
+                    self.hold_remaining = self.hold_samples;
+                    self.stage = Stage::Hold;
+                }
+            }
+            Stage::Hold => {
+                if self.hold_remaining == 0 {
+                    self.stage = Stage::Decay;
+                } else {
+                    self.hold_remaining -= 1;
+                }
+            }
+            Stage::Decay => {
+                self.value -= self.decay_step;
+                if self.value <= 0.0 {
+                    self.value = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+        self.value
+    }
+}
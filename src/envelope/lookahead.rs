@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+pub const MAX_LOOKAHEAD: Duration = Duration::from_millis(10);
+/// How long the output crossfades from the old delay to the new one
+const DELAY_FADE_TIME: Duration = Duration::from_millis(10);
+
+/// Delays the dry signal so the envelope follower, which hears the input
+/// straight away, gets to read ahead of it. The buffer is sized for
+/// `MAX_LOOKAHEAD` when it's created, so changing the delay never allocates.
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    position: usize,
+    delay: usize,
+    target_delay: usize,
+    /// The delay being faded out while a change of delay crossfades
+    previous_delay: Option<usize>,
+    fade_position: usize,
+    fade_length: usize,
+}
+
+impl DelayLine {
+    pub fn new(sample_rate: f32) -> Self {
+        let capacity = (sample_rate * MAX_LOOKAHEAD.as_secs_f32()).ceil() as usize + 1;
+        Self {
+            buffer: vec![0.0; capacity],
+            position: 0,
+            delay: 0,
+            target_delay: 0,
+            previous_delay: None,
+            fade_position: 0,
+            fade_length: ((sample_rate * DELAY_FADE_TIME.as_secs_f32()) as usize).max(1),
+        }
+    }
+
+    /// The delay is capped at `MAX_LOOKAHEAD`. A new delay fades in over
+    /// `DELAY_FADE_TIME`, so automating it doesn't click.
+    pub fn set_delay(&mut self, samples: usize) {
+        self.target_delay = samples.min(self.buffer.len() - 1);
+    }
+
+    /// Clears the line and jumps straight to the latest delay
+    pub fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.position = 0;
+        self.delay = self.target_delay;
+        self.previous_delay = None;
+    }
+
+    fn read(&self, delay: usize) -> f32 {
+        self.buffer[(self.position + self.buffer.len() - delay) % self.buffer.len()]
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        self.buffer[self.position] = sample;
+
+        // a change that arrives mid fade waits for that fade to finish
+        if self.previous_delay.is_none() && self.target_delay != self.delay {
+            self.previous_delay = Some(self.delay);
+            self.delay = self.target_delay;
+            self.fade_position = 0;
+        }
+
+        let mut out = self.read(self.delay);
+        if let Some(previous_delay) = self.previous_delay {
+            // both taps read the same signal, so a linear fade keeps the level
+            let fade = self.fade_position as f32 / self.fade_length as f32;
+            out = self.read(previous_delay) * (1.0 - fade) + out * fade;
+            self.fade_position += 1;
+            if self.fade_position >= self.fade_length {
+                self.previous_delay = None;
+            }
+        }
+
+        self.position = (self.position + 1) % self.buffer.len();
+        out
+    }
+}
@@ -1 +1,4 @@
 pub mod follower;
+pub mod gate;
+pub mod lookahead;
+pub mod vocoder;
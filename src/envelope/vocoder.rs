@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::envelope::follower::{Detector, EnvelopeFollower};
+use crate::filters::{biquad::Biquad, coefficients::FilterType};
+
+pub const MAX_VOCODER_BANDS: usize = 16;
+
+/// One band of the vocoder. The analysis filter picks the band out of the
+/// input, and the synthesis filter picks the same band out of the noise.
+struct VocoderBand {
+    analysis: Biquad,
+    synthesis: Biquad,
+    envelope_follower: EnvelopeFollower,
+    envelope: f32,
+}
+
+impl VocoderBand {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            analysis: Default::default(),
+            synthesis: Default::default(),
+            envelope_follower: EnvelopeFollower::new(&sample_rate),
+            envelope: 0.0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.analysis.reset();
+        self.synthesis.reset();
+        self.envelope = 0.0;
+    }
+}
+
+/// Splits the input into bands spaced evenly in octaves, and gives each band
+/// of the noise the envelope of the matching band of the input. All bands are
+/// allocated up front, and only the first `active_bands` are run.
+pub struct Vocoder {
+    bands: Vec<VocoderBand>,
+    active_bands: usize,
+}
+
+impl Vocoder {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            bands: (0..MAX_VOCODER_BANDS)
+                .map(|_| VocoderBand::new(sample_rate))
+                .collect(),
+            active_bands: 0,
+        }
+    }
+
+    /// Spreads `num_bands` bands from `low` to `high`
+    pub fn update(&mut self, low: f32, high: f32, num_bands: usize, sample_rate: f32) {
+        let num_bands = num_bands.clamp(1, MAX_VOCODER_BANDS);
+        let ratio = (high / low).powf(1.0 / num_bands as f32);
+        // a band from f to f * ratio has this Q at its geometric center
+        let q = ratio.sqrt() / (ratio - 1.0);
+
+        for band in &mut self.bands[self.active_bands.min(num_bands)..num_bands] {
+            band.reset();
+        }
+        self.active_bands = num_bands;
+
+        for (idx, band) in self.bands.iter_mut().take(num_bands).enumerate() {
+            let center = low * ratio.powf(idx as f32 + 0.5);
+            band.analysis
+                .coefficients
+                .update(center, q, sample_rate, FilterType::Bandpass);
+            band.synthesis
+                .coefficients
+                .update(center, q, sample_rate, FilterType::Bandpass);
+        }
+    }
+
+    pub fn set_times(
+        &mut self,
+        attack: Duration,
+        release: Duration,
+        hold: Duration,
+        rms_window: Duration,
+    ) {
+        for band in self.bands.iter_mut() {
+            band.envelope_follower
+                .set_times(attack, release, hold, rms_window);
+        }
+    }
+
+    pub fn set_detector(&mut self, detector: Detector) {
+        for band in self.bands.iter_mut() {
+            band.envelope_follower.set_detector(detector);
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        for band in self.bands.iter_mut() {
+            band.envelope_follower.set_sample_rate(sample_rate);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.bands.iter_mut().for_each(VocoderBand::reset);
+    }
+
+    /// Follows the envelope of every band of the input
+    pub fn analyze(&mut self, sample: f32) {
+        for band in self.bands.iter_mut().take(self.active_bands) {
+            let band_sample = band.analysis.process(sample);
+            band.envelope = band.envelope_follower.process(band_sample);
+        }
+    }
+
+    /// Sums every band of the noise, each at the level of its input band
+    pub fn synthesize(&mut self, noise_sample: f32) -> f32 {
+        self.bands
+            .iter_mut()
+            .take(self.active_bands)
+            .map(|band| band.synthesis.process(noise_sample) * band.envelope)
+            .sum()
+    }
+}
@@ -11,6 +11,11 @@ pub struct Biquad {
 }
 
 impl Biquad {
+    pub fn reset(&mut self) {
+        self.s1 = 0.0;
+        self.s2 = 0.0;
+    }
+
     pub fn process(&mut self, sample: f32) -> f32 {
         let out = self.coefficients.b0 * sample + self.s1;
         self.s1 = self.s2 + self.coefficients.b1 * sample - self.coefficients.a1 * out;
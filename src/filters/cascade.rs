@@ -0,0 +1,138 @@
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+
+use crate::filters::{
+    biquad::Biquad,
+    coefficients::FilterType,
+    svf::{Svf, SvfOutput},
+};
+use crate::params::{FilterEngine, FilterResponse, FilterSlope};
+
+/// Enough second order sections for a 48 dB/oct filter
+pub const MAX_STAGES: usize = 4;
+
+/// A chain of second order sections that together form a Butterworth or
+/// Linkwitz-Riley filter of up to 8th order. The sections are either biquads
+/// or state variable filters, depending on the engine.
+#[derive(Default)]
+pub struct FilterCascade {
+    stages: [Biquad; MAX_STAGES],
+    svf_stages: [Svf; MAX_STAGES],
+    active_stages: usize,
+    /// Set while the state variable filters are in use
+    svf_output: Option<SvfOutput>,
+    engine: FilterEngine,
+}
+
+impl FilterCascade {
+    pub fn set_engine(&mut self, engine: FilterEngine) {
+        if engine != self.engine {
+            // the engine that takes over still holds state from when it was last used
+            self.reset();
+            self.engine = engine;
+        }
+    }
+
+    /// `q` is the Q of the whole filter. At 1/sqrt(2) the response is exactly
+    /// Butterworth or Linkwitz-Riley, and anything above that adds resonance
+    /// to the last, sharpest section.
+    pub fn update(
+        &mut self,
+        fc: f32,
+        q: f32,
+        sample_rate: f32,
+        filter_type: FilterType,
+        slope: FilterSlope,
+        response: FilterResponse,
+    ) {
+        let (mut qs, active_stages) = stage_qs(slope, response);
+        qs[active_stages - 1] *= q / FRAC_1_SQRT_2;
+
+        // stages that were switched off still hold their old state
+        let newly_active = self.active_stages.min(active_stages)..active_stages;
+        self.stages[newly_active.clone()]
+            .iter_mut()
+            .for_each(Biquad::reset);
+        self.svf_stages[newly_active]
+            .iter_mut()
+            .for_each(Svf::reset);
+        self.active_stages = active_stages;
+
+        self.svf_output = match self.engine {
+            FilterEngine::Biquad => None,
+            FilterEngine::StateVariable => SvfOutput::from_filter_type(filter_type),
+        };
+
+        match self.svf_output {
+            Some(_) => {
+                for (stage, q) in self.svf_stages.iter_mut().zip(qs).take(active_stages) {
+                    stage.update(fc, q, sample_rate);
+                }
+            }
+            None => {
+                for (stage, q) in self.stages.iter_mut().zip(qs).take(active_stages) {
+                    stage.coefficients.update(fc, q, sample_rate, filter_type);
+                }
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.stages.iter_mut().for_each(Biquad::reset);
+        self.svf_stages.iter_mut().for_each(Svf::reset);
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        match self.svf_output {
+            Some(output) => self.svf_stages[..self.active_stages]
+                .iter_mut()
+                .fold(sample, |sample, stage| stage.process(sample, output)),
+            None => self.stages[..self.active_stages]
+                .iter_mut()
+                .fold(sample, |sample, stage| stage.process(sample)),
+        }
+    }
+}
+
+/// Q of one second order section of a Butterworth filter. Odd orders also
+/// have a first order section, which isn't covered here.
+fn butterworth_q(order: usize, section: usize) -> f32 {
+    let angle = (2 * section + 1 + order % 2) as f32 * PI / (2 * order) as f32;
+    1.0 / (2.0 * angle.cos())
+}
+
+/// Q of every second order section needed for the given slope, along with
+/// how many sections are used
+fn stage_qs(slope: FilterSlope, response: FilterResponse) -> ([f32; MAX_STAGES], usize) {
+    let order = match slope {
+        FilterSlope::Twelve => 2,
+        FilterSlope::TwentyFour => 4,
+        FilterSlope::ThirtySix => 6,
+        FilterSlope::FortyEight => 8,
+    };
+    let stages = order / 2;
+    let mut qs = [0.0; MAX_STAGES];
+
+    match response {
+        FilterResponse::Butterworth => {
+            for (section, q) in qs.iter_mut().take(stages).enumerate() {
+                *q = butterworth_q(order, section);
+            }
+        }
+        // a Linkwitz-Riley filter is a Butterworth filter of half the order,
+        // applied twice. Two first order sections in a row make one second
+        // order section with a Q of 0.5.
+        FilterResponse::LinkwitzRiley => {
+            let half_order = order / 2;
+            for section in 0..half_order / 2 {
+                let q = butterworth_q(half_order, section);
+                qs[2 * section] = q;
+                qs[2 * section + 1] = q;
+            }
+            if half_order % 2 == 1 {
+                qs[stages - 1] = 0.5;
+            }
+        }
+    }
+
+    (qs, stages)
+}
@@ -1,8 +1,15 @@
-use std::f32::consts::{FRAC_1_SQRT_2, TAU};
+use std::f32::consts::TAU;
 
+/// Peak and shelf filters carry their gain in dB
+#[derive(Clone, Copy)]
 pub enum FilterType {
     Lowpass,
     Highpass,
+    Bandpass,
+    Notch,
+    Peak(f32),
+    LowShelf(f32),
+    HighShelf(f32),
 }
 
 /// Based on coefficient cookbook here: http://shepazu.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html
@@ -31,10 +38,10 @@ impl Default for FilterCoefficients {
 }
 
 impl FilterCoefficients {
-    fn lowpass(&mut self, fc: f32, sample_rate: f32) {
+    fn lowpass(&mut self, fc: f32, q: f32, sample_rate: f32) {
         let omega_c = TAU * (fc / sample_rate);
         let cos_omega_c = omega_c.cos();
-        let alpha = omega_c.sin() / (2.0 * FRAC_1_SQRT_2);
+        let alpha = omega_c.sin() / (2.0 * q);
 
         self.a0 = 1.0 + alpha;
         self.b0 = ((1.0 - cos_omega_c) / 2.0) / self.a0;
@@ -44,10 +51,10 @@ impl FilterCoefficients {
         self.a2 = (1.0 - alpha) / self.a0;
     }
 
-    fn highpass(&mut self, fc: f32, sample_rate: f32) {
+    fn highpass(&mut self, fc: f32, q: f32, sample_rate: f32) {
         let omega_c = TAU * (fc / sample_rate);
         let cos_omega_c = omega_c.cos();
-        let alpha = omega_c.sin() / (2.0 * FRAC_1_SQRT_2);
+        let alpha = omega_c.sin() / (2.0 * q);
 
         self.a0 = 1.0 + alpha;
         self.b0 = ((1.0 + cos_omega_c) / 2.0) / self.a0;
@@ -57,10 +64,113 @@ impl FilterCoefficients {
         self.a2 = (1.0 - alpha) / self.a0;
     }
 
-    pub fn update(&mut self, fc: f32, sample_rate: f32, filter_type: FilterType) {
+    /// Constant 0 dB peak gain
+    fn bandpass(&mut self, fc: f32, q: f32, sample_rate: f32) {
+        let omega_c = TAU * (fc / sample_rate);
+        let cos_omega_c = omega_c.cos();
+        let alpha = omega_c.sin() / (2.0 * q);
+
+        self.a0 = 1.0 + alpha;
+        self.b0 = alpha / self.a0;
+        self.b1 = 0.0;
+        self.b2 = -alpha / self.a0;
+        self.a1 = (-2.0 * cos_omega_c) / self.a0;
+        self.a2 = (1.0 - alpha) / self.a0;
+    }
+
+    fn notch(&mut self, fc: f32, q: f32, sample_rate: f32) {
+        let omega_c = TAU * (fc / sample_rate);
+        let cos_omega_c = omega_c.cos();
+        let alpha = omega_c.sin() / (2.0 * q);
+
+        self.a0 = 1.0 + alpha;
+        self.b0 = 1.0 / self.a0;
+        self.b1 = (-2.0 * cos_omega_c) / self.a0;
+        self.b2 = 1.0 / self.a0;
+        self.a1 = (-2.0 * cos_omega_c) / self.a0;
+        self.a2 = (1.0 - alpha) / self.a0;
+    }
+
+    fn peak(&mut self, fc: f32, q: f32, gain_db: f32, sample_rate: f32) {
+        let a = 10f32.powf(gain_db / 40.0);
+        let omega_c = TAU * (fc / sample_rate);
+        let cos_omega_c = omega_c.cos();
+        let alpha = omega_c.sin() / (2.0 * q);
+
+        self.a0 = 1.0 + alpha / a;
+        self.b0 = (1.0 + alpha * a) / self.a0;
+        self.b1 = (-2.0 * cos_omega_c) / self.a0;
+        self.b2 = (1.0 - alpha * a) / self.a0;
+        self.a1 = (-2.0 * cos_omega_c) / self.a0;
+        self.a2 = (1.0 - alpha / a) / self.a0;
+    }
+
+    fn low_shelf(&mut self, fc: f32, q: f32, gain_db: f32, sample_rate: f32) {
+        let a = 10f32.powf(gain_db / 40.0);
+        let omega_c = TAU * (fc / sample_rate);
+        let cos_omega_c = omega_c.cos();
+        let alpha = omega_c.sin() / (2.0 * q);
+        let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        self.a0 = (a + 1.0) + (a - 1.0) * cos_omega_c + two_sqrt_a_alpha;
+        self.b0 = a * ((a + 1.0) - (a - 1.0) * cos_omega_c + two_sqrt_a_alpha) / self.a0;
+        self.b1 = 2.0 * a * ((a - 1.0) - (a + 1.0) * cos_omega_c) / self.a0;
+        self.b2 = a * ((a + 1.0) - (a - 1.0) * cos_omega_c - two_sqrt_a_alpha) / self.a0;
+        self.a1 = -2.0 * ((a - 1.0) + (a + 1.0) * cos_omega_c) / self.a0;
+        self.a2 = ((a + 1.0) + (a - 1.0) * cos_omega_c - two_sqrt_a_alpha) / self.a0;
+    }
+
+    fn high_shelf(&mut self, fc: f32, q: f32, gain_db: f32, sample_rate: f32) {
+        let a = 10f32.powf(gain_db / 40.0);
+        let omega_c = TAU * (fc / sample_rate);
+        let cos_omega_c = omega_c.cos();
+        let alpha = omega_c.sin() / (2.0 * q);
+        let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        self.a0 = (a + 1.0) - (a - 1.0) * cos_omega_c + two_sqrt_a_alpha;
+        self.b0 = a * ((a + 1.0) + (a - 1.0) * cos_omega_c + two_sqrt_a_alpha) / self.a0;
+        self.b1 = -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_omega_c) / self.a0;
+        self.b2 = a * ((a + 1.0) + (a - 1.0) * cos_omega_c - two_sqrt_a_alpha) / self.a0;
+        self.a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cos_omega_c) / self.a0;
+        self.a2 = ((a + 1.0) - (a - 1.0) * cos_omega_c - two_sqrt_a_alpha) / self.a0;
+    }
+
+    /// Bilinear transform of an analog second order section
+    /// H(s) = (b[0]s^2 + b[1]s + b[2]) / (a[0]s^2 + a[1]s + a[2])
+    pub fn bilinear(&mut self, b: [f32; 3], a: [f32; 3], sample_rate: f32) {
+        let k = 2.0 * sample_rate;
+        let k_squared = k * k;
+
+        self.a0 = a[0] * k_squared + a[1] * k + a[2];
+        self.b0 = (b[0] * k_squared + b[1] * k + b[2]) / self.a0;
+        self.b1 = 2.0 * (b[2] - b[0] * k_squared) / self.a0;
+        self.b2 = (b[0] * k_squared - b[1] * k + b[2]) / self.a0;
+        self.a1 = 2.0 * (a[2] - a[0] * k_squared) / self.a0;
+        self.a2 = (a[0] * k_squared - a[1] * k + a[2]) / self.a0;
+    }
+
+    /// Magnitude of the frequency response at `freq`
+    pub fn magnitude(&self, freq: f32, sample_rate: f32) -> f32 {
+        let omega = TAU * freq / sample_rate;
+        let (sin, cos) = omega.sin_cos();
+        let (sin_2, cos_2) = (2.0 * omega).sin_cos();
+
+        let numerator =
+            (self.b0 + self.b1 * cos + self.b2 * cos_2).hypot(self.b1 * sin + self.b2 * sin_2);
+        let denominator =
+            (1.0 + self.a1 * cos + self.a2 * cos_2).hypot(self.a1 * sin + self.a2 * sin_2);
+        numerator / denominator
+    }
+
+    pub fn update(&mut self, fc: f32, q: f32, sample_rate: f32, filter_type: FilterType) {
         match filter_type {
-            FilterType::Lowpass => self.lowpass(fc, sample_rate),
-            FilterType::Highpass => self.highpass(fc, sample_rate),
+            FilterType::Lowpass => self.lowpass(fc, q, sample_rate),
+            FilterType::Highpass => self.highpass(fc, q, sample_rate),
+            FilterType::Bandpass => self.bandpass(fc, q, sample_rate),
+            FilterType::Notch => self.notch(fc, q, sample_rate),
+            FilterType::Peak(gain_db) => self.peak(fc, q, gain_db, sample_rate),
+            FilterType::LowShelf(gain_db) => self.low_shelf(fc, q, gain_db, sample_rate),
+            FilterType::HighShelf(gain_db) => self.high_shelf(fc, q, gain_db, sample_rate),
         };
     }
 }
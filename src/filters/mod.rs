@@ -1,2 +1,5 @@
 pub mod biquad;
+pub mod cascade;
 pub mod coefficients;
+pub mod shelf;
+pub mod svf;
@@ -0,0 +1,41 @@
+use std::f32::consts::PI;
+
+/// A first-order shelving section, bilinear transformed from
+/// H(s) = (s + wz) / (s + wp)
+#[derive(Default, Clone, Copy)]
+pub struct ShelfSection {
+    b0: f32,
+    b1: f32,
+    a1: f32,
+    s1: f32,
+}
+
+impl ShelfSection {
+    pub fn update(&mut self, zero_hz: f32, pole_hz: f32, sample_rate: f32) {
+        let k = 2.0 * sample_rate;
+        let warp = |freq: f32| k * (PI * freq.min(0.45 * sample_rate) / sample_rate).tan();
+        let (wz, wp) = (warp(zero_hz), warp(pole_hz));
+
+        self.b0 = (k + wz) / (k + wp);
+        self.b1 = (wz - k) / (k + wp);
+        self.a1 = (wp - k) / (k + wp);
+    }
+
+    /// Squared magnitude of the frequency response at the angular frequency
+    /// whose cosine and sine are given
+    pub fn magnitude_squared(&self, cos: f32, sin: f32) -> f32 {
+        let (num_re, num_im) = (self.b0 + self.b1 * cos, -self.b1 * sin);
+        let (den_re, den_im) = (1.0 + self.a1 * cos, -self.a1 * sin);
+        (num_re * num_re + num_im * num_im) / (den_re * den_re + den_im * den_im)
+    }
+
+    pub fn reset(&mut self) {
+        self.s1 = 0.0;
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        let out = self.b0 * sample + self.s1;
+        self.s1 = self.b1 * sample - self.a1 * out;
+        out
+    }
+}
@@ -0,0 +1,77 @@
+use std::f32::consts::PI;
+
+use crate::filters::coefficients::FilterType;
+
+/// Which of the state variable filter's outputs to use
+#[derive(Clone, Copy, PartialEq)]
+pub enum SvfOutput {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Notch,
+}
+
+impl SvfOutput {
+    /// The output matching a filter type, if the state variable filter has one
+    pub fn from_filter_type(filter_type: FilterType) -> Option<Self> {
+        match filter_type {
+            FilterType::Lowpass => Some(Self::Lowpass),
+            FilterType::Highpass => Some(Self::Highpass),
+            FilterType::Bandpass => Some(Self::Bandpass),
+            FilterType::Notch => Some(Self::Notch),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a topology-preserving transform (zero delay feedback) state
+/// variable filter. Its state is stored as the integrator outputs rather than
+/// past samples, so the cutoff can be modulated at audio rate without the
+/// zipper noise or blowups a direct form biquad gets.
+/// https://cytomic.com/files/dsp/SvfLinearTrapOptimised2.pdf
+#[derive(Default)]
+pub struct Svf {
+    ic1eq: f32,
+    ic2eq: f32,
+
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+}
+
+impl Svf {
+    pub fn update(&mut self, fc: f32, q: f32, sample_rate: f32) {
+        // the prewarped cutoff goes to infinity at Nyquist
+        let fc = fc.min(sample_rate * 0.49);
+        let g = (PI * fc / sample_rate).tan();
+
+        self.k = 1.0 / q;
+        self.a1 = 1.0 / (1.0 + g * (g + self.k));
+        self.a2 = g * self.a1;
+        self.a3 = g * self.a2;
+    }
+
+    pub fn reset(&mut self) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+    }
+
+    pub fn process(&mut self, sample: f32, output: SvfOutput) -> f32 {
+        let v3 = sample - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        let lowpass = v2;
+        let bandpass = v1;
+        let highpass = sample - self.k * v1 - v2;
+        match output {
+            SvfOutput::Lowpass => lowpass,
+            SvfOutput::Highpass => highpass,
+            SvfOutput::Bandpass => bandpass,
+            SvfOutput::Notch => lowpass + highpass,
+        }
+    }
+}
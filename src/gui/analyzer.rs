@@ -5,8 +5,16 @@ use realfft::num_complex::Complex;
 use std::sync::{atomic::Ordering, Arc, Mutex};
 use triple_buffer::Output;
 
+use crate::envelope::follower::EnvelopeMode;
+use crate::params::NoiseParams;
+
 pub type SpectrumBuffer = Arc<Mutex<Output<Vec<Complex<f32>>>>>;
 
+// the input meter covers the same range as the gate threshold
+const METER_MIN_DB: f32 = -60.0;
+const METER_MAX_DB: f32 = 0.0;
+const METER_WIDTH: f32 = 6.0;
+
 fn filter_frequency_range() -> FloatRange {
     FloatRange::Skewed {
         min: 5.0,
@@ -21,7 +29,9 @@ fn filter_frequency_range() -> FloatRange {
 pub struct SpectrumAnalyzer {
     spectrum: SpectrumBuffer,
     sample_rate: Arc<AtomicF32>,
+    input_level: Arc<AtomicF32>,
     frequency_range: FloatRange,
+    params: Arc<NoiseParams>,
 }
 
 impl SpectrumAnalyzer {
@@ -29,11 +39,15 @@ impl SpectrumAnalyzer {
         cx: &mut Context,
         spectrum: SpectrumBuffer,
         sample_rate: Arc<AtomicF32>,
+        input_level: Arc<AtomicF32>,
+        params: Arc<NoiseParams>,
     ) -> Handle<Self> {
         Self {
             spectrum,
             sample_rate,
+            input_level,
             frequency_range: filter_frequency_range(),
+            params,
         }
         .build(cx, |_cx| ())
     }
@@ -68,6 +82,37 @@ impl SpectrumAnalyzer {
 
         canvas.stroke_path(&mut path, &line_paint);
     }
+
+    /// Meter along the right edge showing the envelope follower level the
+    /// gate listens to, with a tick at the gate threshold. The spectrum is a
+    /// per-bin level of the output, so the threshold can't be drawn against
+    /// it.
+    fn draw_gate_meter(&self, cx: &mut DrawContext, canvas: &mut Canvas, bounds: BoundingBox) {
+        let dpi_factor = cx.style.dpi_factor as f32;
+        let width = METER_WIDTH * dpi_factor;
+        let x = bounds.x + bounds.w - width;
+        let meter_y = |db: f32| {
+            let h = ((db - METER_MIN_DB) / (METER_MAX_DB - METER_MIN_DB)).clamp(0., 1.);
+            bounds.y + bounds.h * (1. - h)
+        };
+
+        let level_y = meter_y(util::gain_to_db(self.input_level.load(Ordering::Relaxed)));
+        let mut level = vg::Path::new();
+        level.rect(x, level_y, width, bounds.y + bounds.h - level_y);
+        canvas.fill_path(
+            &mut level,
+            &vg::Paint::color(vg::Color::rgbaf(0.12, 0.69, 0.46, 0.8)),
+        );
+
+        let threshold_y = meter_y(self.params.gate_threshold.value());
+        let mut threshold = vg::Path::new();
+        threshold.move_to(x - width, threshold_y);
+        threshold.line_to(x + width, threshold_y);
+        canvas.stroke_path(
+            &mut threshold,
+            &vg::Paint::color(vg::Color::rgbaf(0.76, 0.54, 0.1, 0.8)).with_line_width(dpi_factor),
+        );
+    }
 }
 
 impl View for SpectrumAnalyzer {
@@ -82,5 +127,8 @@ impl View for SpectrumAnalyzer {
         }
 
         self.draw_analyzer(cx, canvas, bounds);
+        if self.params.env_mode.value() == EnvelopeMode::Gate {
+            self.draw_gate_meter(cx, canvas, bounds);
+        }
     }
 }
@@ -0,0 +1,35 @@
+use nih_plug_vizia::vizia::{prelude::*, view::View};
+
+pub struct FaderContainer {}
+
+impl View for FaderContainer {
+    fn element(&self) -> Option<&'static str> {
+        Some("fader-vstack")
+    }
+}
+
+impl FaderContainer {
+    pub fn new<L, V, F>(
+        cx: &mut Context,
+        label: String,
+        lens: L,
+        value_lens: V,
+        on_change_callback: F,
+    ) -> Handle<Self>
+    where
+        L: Lens<Target = f32>,
+        V: Lens<Target = String>,
+        F: 'static + Fn(&mut EventContext, f32),
+    {
+        Self {}.build(cx, |cx| {
+            VStack::new(cx, |cx| {
+                Label::new(cx, &label).class("fader-label");
+                Slider::new(cx, lens)
+                    .on_changing(on_change_callback)
+                    .class("fader");
+                Label::new(cx, value_lens).class("fader-label");
+            })
+            .child_space(Stretch(1.0));
+        })
+    }
+}
@@ -1,3 +1,4 @@
 pub mod analyzer;
 pub mod debug;
+pub mod fader;
 pub mod knob;
@@ -1,7 +1,5 @@
-use filters::coefficients::FilterType;
+use envelope::follower::EnvelopeSource;
 use nih_plug::prelude::*;
-use noise::NoiseConfig;
-use params::NoiseType;
 use std::sync::{atomic::Ordering, Arc};
 
 mod config;
@@ -27,11 +25,21 @@ impl Plugin for noise::Noise {
         AudioIOLayout {
             main_input_channels: NonZeroU32::new(2),
             main_output_channels: NonZeroU32::new(2),
+            aux_input_ports: &[new_nonzero_u32(2)],
+            names: PortNames {
+                aux_inputs: &["Sidechain"],
+                ..PortNames::const_default()
+            },
             ..AudioIOLayout::const_default()
         },
         AudioIOLayout {
             main_input_channels: NonZeroU32::new(1),
             main_output_channels: NonZeroU32::new(1),
+            aux_input_ports: &[new_nonzero_u32(1)],
+            names: PortNames {
+                aux_inputs: &["Sidechain"],
+                ..PortNames::const_default()
+            },
             ..AudioIOLayout::const_default()
         },
     ];
@@ -42,43 +50,60 @@ impl Plugin for noise::Noise {
         self.params.clone()
     }
 
+    fn filter_state(state: &mut PluginState) {
+        // the HPF used to go from 5 Hz to 5 kHz and the LPF from 5 kHz to
+        // 20 kHz. Both now share one range, which a 5 Hz HPF falls outside of.
+        for id in ["highpass-frequency-cutoff", "lowpass-frequency-cutoff"] {
+            if let Some(ParamValue::F32(fc)) = state.params.get_mut(id) {
+                *fc = fc.clamp(params::MIN_CUTOFF, params::MAX_CUTOFF);
+            }
+        }
+    }
+
     fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
         editor::create(
             self.params.clone(),
             self.params.editor_state.clone(),
             self.debug.clone(),
             self.sample_rate.clone(),
+            self.input_level.clone(),
             self.spectrum_output_buffer.clone(),
         )
     }
 
     fn initialize(
         &mut self,
-        _audio_io_layout: &AudioIOLayout,
+        audio_io_layout: &AudioIOLayout,
         _buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
+        context: &mut impl InitContext<Self>,
     ) -> bool {
         let sr = _buffer_config.sample_rate;
         self.sample_rate.store(sr, Ordering::Relaxed);
         self.spectrum.set_sample_rate(sr);
+        let num_channels = audio_io_layout
+            .main_output_channels
+            .map(NonZeroU32::get)
+            .unwrap_or(1);
+        self.set_layout(num_channels as usize, sr);
+        self.should_update_filter.store(true, Ordering::Relaxed);
+        self.should_update_envelope.store(true, Ordering::Relaxed);
+
+        let lookahead = self.lookahead_samples(sr);
+        self.set_lookahead(lookahead);
+        context.set_latency_samples(lookahead as u32);
 
         true
     }
 
     fn reset(&mut self) {
-        match self.params.noise_type.value() {
-            NoiseType::White => self.white.reset(),
-            NoiseType::Pink => self.pink.reset(),
-            NoiseType::Brown => self.brown.reset(),
-            NoiseType::Violet => self.violet.reset(),
-        }
+        self.reset_generators();
     }
 
     fn process(
         &mut self,
         buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
+        aux: &mut AuxiliaryBuffers,
+        context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
         let gain = self.params.gain.smoothed.next();
         let mix_level = self.params.mix.smoothed.next();
@@ -87,45 +112,50 @@ impl Plugin for noise::Noise {
         // update lowpass and highpass filter coefficients only if needed
         if self
             .should_update_filter
-            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
             .is_ok()
         {
-            let lpf_fc = self.params.lpf_fc.smoothed.next();
-            let hpf_fc = self.params.hpf_fc.smoothed.next();
-            self.lpf
-                .coefficients
-                .update(lpf_fc, sr, FilterType::Lowpass);
-            self.hpf
-                .coefficients
-                .update(hpf_fc, sr, FilterType::Highpass);
+            self.update_filters(sr, true);
         }
 
-        for channel_samples in buffer.iter_samples() {
-            if self.params.lpf_fc.smoothed.is_smoothing() {
-                let lpf_fc = self.params.lpf_fc.smoothed.next();
-                self.lpf
-                    .coefficients
-                    .update(lpf_fc, sr, FilterType::Lowpass);
-            }
+        let lookahead = self.lookahead_samples(sr);
+        if lookahead != self.lookahead {
+            self.set_lookahead(lookahead);
+            context.set_latency_samples(lookahead as u32);
+        }
 
-            if self.params.hpf_fc.smoothed.is_smoothing() {
-                let hpf_fc = self.params.hpf_fc.smoothed.next();
-                self.hpf
-                    .coefficients
-                    .update(hpf_fc, sr, FilterType::Highpass);
+        // likewise for the envelope follower times and detector
+        if self
+            .should_update_envelope
+            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            self.update_envelopes();
+        }
+
+        let sidechain = match self.params.env_source.value() {
+            EnvelopeSource::Main => None,
+            EnvelopeSource::Sidechain => aux.inputs.first().map(Buffer::as_slice_immutable),
+        };
+
+        for (sample_idx, mut channel_samples) in buffer.iter_samples().enumerate() {
+            self.update_filters(sr, false);
+
+            self.next_frame();
+            self.follow_tape(channel_samples.iter_mut().map(|sample| *sample));
+            match sidechain {
+                Some(sidechain) => {
+                    self.follow_frame(sidechain.iter().map(|channel| channel[sample_idx]))
+                }
+                None => self.follow_frame(channel_samples.iter_mut().map(|sample| *sample)),
             }
 
-            for sample in channel_samples {
-                *sample = self.process(*sample);
+            for (channel, sample) in channel_samples.into_iter().enumerate() {
+                *sample = self.process(channel, *sample);
 
                 if cfg!(debug_assertions) {
-                    self.debug.update(
-                        *sample,
-                        sr,
-                        mix_level,
-                        gain,
-                        self.envelope_follower.process(*sample),
-                    );
+                    self.debug
+                        .update(*sample, sr, mix_level, gain, self.envelope_frame[channel]);
                 }
             }
         }
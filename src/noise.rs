@@ -1,33 +1,52 @@
 use atomic_float::AtomicF32;
 use std::{
+    f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, PI, TAU},
     mem,
-    sync::{atomic::AtomicBool, Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 
 use crate::config;
-use crate::envelope::follower::{EnvelopeFollower, EnvelopeMode};
-use crate::filters::biquad::Biquad;
+use crate::envelope::{
+    follower::{EnvelopeFollower, EnvelopeMode},
+    gate::Gate,
+    lookahead::DelayLine,
+    vocoder::Vocoder,
+};
+use crate::filters::{
+    biquad::Biquad, cascade::FilterCascade, coefficients::FilterType, shelf::ShelfSection,
+};
 use crate::gui;
-use crate::params::{NoiseParams, NoiseType};
+use crate::params::{
+    HumFrequency, NoiseParams, NoiseType, StereoMode, TapeSpeed, ToneType, WhiteDistribution,
+    LAYER_COUNT, MAX_CUTOFF, MIN_CUTOFF,
+};
 use crate::spectrum::Spectrum;
-use rand::{rngs::StdRng, SeedableRng};
-use rand_distr::{Distribution, Uniform};
+use nih_plug::prelude::{util, FloatParam};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal, Uniform};
 
 pub struct Noise {
     pub params: Arc<NoiseParams>,
-    pub rng: StdRng,
-    pub white: White,
-    pub pink: Pink,
-    pub brown: Brown,
-    pub violet: Violet,
+    pub channels: Vec<NoiseChannel>,
+    pub noise_frame: Vec<f32>,
+    pub envelope_frame: Vec<f32>,
+    pub lookahead: usize,
+    pub settings: FrameSettings,
+    pub active_type: NoiseType,
+    pub crossfade: Crossfade,
     pub debug: config::Debug,
     pub sample_rate: Arc<AtomicF32>,
+    /// The loudest envelope follower level, before gate mode turns it into
+    /// bursts, for the editor's input meter
+    pub input_level: Arc<AtomicF32>,
     pub spectrum: Spectrum,
     pub spectrum_output_buffer: gui::analyzer::SpectrumBuffer,
-    pub lpf: Biquad,
-    pub hpf: Biquad,
     pub should_update_filter: Arc<AtomicBool>,
-    pub envelope_follower: EnvelopeFollower,
+    pub should_update_envelope: Arc<AtomicBool>,
 }
 
 impl Default for Noise {
@@ -37,46 +56,306 @@ impl Default for Noise {
         let sample_rate = Arc::new(AtomicF32::new(44.1e3));
 
         let should_update_filter = Arc::new(AtomicBool::new(true));
-        let env = EnvelopeFollower::new(&sample_rate.load(std::sync::atomic::Ordering::Relaxed));
+        let should_update_envelope = Arc::new(AtomicBool::new(true));
+        let params = Arc::new(NoiseParams::new(
+            should_update_filter.clone(),
+            should_update_envelope.clone(),
+        ));
 
         Self {
-            params: Arc::new(NoiseParams::new(should_update_filter)),
-            rng: StdRng::from_entropy(),
-            white: White::new(),
-            pink: Pink::new(),
-            brown: Brown::new(0.99),
-            violet: Violet::new(),
+            channels: (0..2)
+                .map(|_| NoiseChannel::new(sample_rate.load(std::sync::atomic::Ordering::Relaxed)))
+                .collect(),
+            noise_frame: vec![0.0; 2],
+            envelope_frame: vec![0.0; 2],
+            lookahead: 0,
+            settings: FrameSettings::next(&params),
+            params,
+            active_type: NoiseType::White,
+            crossfade: Crossfade::new(sample_rate.load(std::sync::atomic::Ordering::Relaxed)),
             debug: config::Debug::default(),
             sample_rate,
+            input_level: Arc::new(AtomicF32::new(0.0)),
             spectrum,
             spectrum_output_buffer,
-            lpf: Default::default(),
-            hpf: Default::default(),
-            should_update_filter: Arc::new(AtomicBool::new(false)),
-            envelope_follower: env,
+            should_update_filter,
+            should_update_envelope,
         }
     }
 }
 
 impl Noise {
-    fn next(&mut self) -> f32 {
-        let noise_sample = match self.params.noise_type.value() {
-            NoiseType::White => self.white.next(&mut self.rng),
-            NoiseType::Pink => self.pink.next(&mut self.rng),
-            NoiseType::Brown => self.brown.next(&mut self.rng),
-            NoiseType::Violet => self.violet.next(&mut self.rng),
+    /// Sets up generator, filter and envelope state for every output channel. This allocates, so it
+    /// should only be called from `initialize`.
+    pub fn set_layout(&mut self, num_channels: usize, sample_rate: f32) {
+        self.channels
+            .resize_with(num_channels, || NoiseChannel::new(sample_rate));
+        self.noise_frame.resize(num_channels, 0.0);
+        self.envelope_frame.resize(num_channels, 0.0);
+        self.channels
+            .iter_mut()
+            .for_each(|channel| channel.set_sample_rate(sample_rate));
+        self.crossfade.set_sample_rate(sample_rate);
+    }
+
+    pub fn reset_generators(&mut self) {
+        self.active_type = self.params.noise_type.value();
+        self.crossfade.from = None;
+        self.channels.iter_mut().for_each(NoiseChannel::reset);
+    }
+
+    /// Moves the generator settings that are smoothed along by one sample and
+    /// fills `noise_frame` with the next noise sample for every channel
+    pub fn next_frame(&mut self) {
+        // a type change that arrives mid fade waits for that fade to finish
+        let noise_type = self.params.noise_type.value();
+        if self.crossfade.from.is_none() && noise_type != self.active_type {
+            self.crossfade.start(self.active_type);
+            self.active_type = noise_type;
+        }
+        self.crossfade.step();
+
+        self.settings = FrameSettings::next(&self.params);
+        let stereo_width = self.params.stereo_width.smoothed.next();
+
+        // every channel keeps generating in mono too, so switching back to
+        // decorrelated doesn't resume the other channels from stale state
+        for (channel, noise_sample) in self.channels.iter_mut().zip(self.noise_frame.iter_mut()) {
+            channel.apply(&self.settings);
+            *noise_sample = channel.next(self.active_type, &self.crossfade, &self.settings);
+        }
+
+        match self.params.stereo_mode.value() {
+            StereoMode::Mono => {
+                let noise_sample = self.noise_frame[0];
+                self.noise_frame.fill(noise_sample);
+            }
+            StereoMode::Decorrelated => {
+                if let [left, right] = self.noise_frame.as_mut_slice() {
+                    // the channels are uncorrelated, so mid and side each carry half
+                    // their power and the width has to be compensated to keep it
+                    let gain = (2.0 / (1.0 + stereo_width * stereo_width)).sqrt();
+                    let mid = (*left + *right) * 0.5 * gain;
+                    let side = (*left - *right) * 0.5 * stereo_width * gain;
+                    *left = mid + side;
+                    *right = mid - side;
+                }
+            }
+        }
+    }
+
+    /// Recalculates the LPF, HPF and tone filter coefficients of every channel. Unless `force`
+    /// is set, a filter is only touched while its cutoff or Q is smoothing.
+    pub fn update_filters(&mut self, sample_rate: f32, force: bool) {
+        let params = &self.params;
+        let (lpf_fc, hpf_fc) = if params.filter_link.value() {
+            let should_update = force
+                || params.filter_center.smoothed.is_smoothing()
+                || params.filter_width.smoothed.is_smoothing()
+                || params.lpf_q.smoothed.is_smoothing()
+                || params.hpf_q.smoothed.is_smoothing();
+            if should_update {
+                // the band spans `width` octaves around its center
+                let center = params.filter_center.smoothed.next();
+                let half_width = 2f32.powf(params.filter_width.smoothed.next() / 2.0);
+                (Some(center * half_width), Some(center / half_width))
+            } else {
+                (None, None)
+            }
+        } else {
+            (
+                (force
+                    || params.lpf_fc.smoothed.is_smoothing()
+                    || params.lpf_q.smoothed.is_smoothing())
+                .then(|| params.lpf_fc.smoothed.next()),
+                (force
+                    || params.hpf_fc.smoothed.is_smoothing()
+                    || params.hpf_q.smoothed.is_smoothing())
+                .then(|| params.hpf_fc.smoothed.next()),
+            )
         };
-        noise_sample
+        let max_cutoff = MAX_CUTOFF.min(sample_rate * 0.49);
+        let lpf = lpf_fc.map(|fc| {
+            (
+                fc.clamp(MIN_CUTOFF, max_cutoff),
+                params.lpf_q.smoothed.next(),
+            )
+        });
+        let hpf = hpf_fc.map(|fc| {
+            (
+                fc.clamp(MIN_CUTOFF, max_cutoff),
+                params.hpf_q.smoothed.next(),
+            )
+        });
+        let tone = (force
+            || params.tone_fc.smoothed.is_smoothing()
+            || params.tone_gain.smoothed.is_smoothing()
+            || params.tone_q.smoothed.is_smoothing())
+        .then(|| {
+            let tone_fc = params.tone_fc.smoothed.next().clamp(MIN_CUTOFF, max_cutoff);
+            let tone_gain = params.tone_gain.smoothed.next();
+            let tone_type = match params.tone_type.value() {
+                ToneType::Peak => FilterType::Peak(tone_gain),
+                ToneType::LowShelf => FilterType::LowShelf(tone_gain),
+                ToneType::HighShelf => FilterType::HighShelf(tone_gain),
+                ToneType::Bandpass => FilterType::Bandpass,
+                ToneType::Notch => FilterType::Notch,
+            };
+            (tone_fc, params.tone_q.smoothed.next(), tone_type)
+        });
+        if lpf.is_none() && hpf.is_none() && tone.is_none() {
+            return;
+        }
+
+        let slope = params.filter_slope.value();
+        let response = params.filter_response.value();
+        let engine = params.filter_engine.value();
+
+        // the vocoder bands aren't smoothed, so they only change when forced.
+        // The highest edge is kept at least half an octave above the lowest.
+        let vocoder_low = params.vocoder_low.value().min(max_cutoff / 1.5);
+        let vocoder_high = params
+            .vocoder_high
+            .value()
+            .clamp(vocoder_low * 1.5, max_cutoff);
+        let vocoder_bands = params.vocoder_bands.value() as usize;
+
+        for channel in self.channels.iter_mut() {
+            if force {
+                channel
+                    .vocoder
+                    .update(vocoder_low, vocoder_high, vocoder_bands, sample_rate);
+            }
+            channel.lpf.set_engine(engine);
+            channel.hpf.set_engine(engine);
+            if let Some((lpf_fc, lpf_q)) = lpf {
+                channel.lpf.update(
+                    lpf_fc,
+                    lpf_q,
+                    sample_rate,
+                    FilterType::Lowpass,
+                    slope,
+                    response,
+                );
+            }
+            if let Some((hpf_fc, hpf_q)) = hpf {
+                channel.hpf.update(
+                    hpf_fc,
+                    hpf_q,
+                    sample_rate,
+                    FilterType::Highpass,
+                    slope,
+                    response,
+                );
+            }
+            if let Some((tone_fc, tone_q, tone_type)) = tone {
+                channel
+                    .tone
+                    .coefficients
+                    .update(tone_fc, tone_q, sample_rate, tone_type);
+            }
+        }
     }
 
-    fn filter_noise(&mut self, sample: f32) -> f32 {
-        let lowpassed_noise = self.lpf.process(sample);
-        self.hpf.process(lowpassed_noise)
+    /// Passes the envelope params on to every channel's envelope follower
+    pub fn update_envelopes(&mut self) {
+        let millis = |param: &FloatParam| Duration::from_secs_f32(param.value() / 1_000.0);
+        let attack = millis(&self.params.env_attack);
+        let release = millis(&self.params.env_release);
+        let hold = millis(&self.params.env_hold);
+        let rms_window = millis(&self.params.env_rms_window);
+        let detector = self.params.env_detector.value();
+
+        let gate_attack = millis(&self.params.gate_attack);
+        let gate_hold = millis(&self.params.gate_hold);
+        let gate_decay = millis(&self.params.gate_decay);
+
+        for channel in self.channels.iter_mut() {
+            channel
+                .envelope_follower
+                .set_times(attack, release, hold, rms_window);
+            channel.envelope_follower.set_detector(detector);
+            channel.gate.set_times(gate_attack, gate_hold, gate_decay);
+            channel.vocoder.set_times(attack, release, hold, rms_window);
+            channel.vocoder.set_detector(detector);
+        }
+    }
+
+    /// Lets the tape modulation noise of every channel follow one frame of
+    /// the main input. This is the signal being "recorded", so it's never
+    /// the sidechain.
+    pub fn follow_tape(&mut self, frame: impl Iterator<Item = f32>) {
+        for (channel, sample) in self.channels.iter_mut().zip(frame) {
+            channel.tape.follow(sample);
+        }
+    }
+
+    /// Runs every channel's envelope follower over one frame of the main or
+    /// sidechain input and fills `envelope_frame`. When the envelopes are
+    /// linked, every channel follows the loudest one so the noise keeps its
+    /// stereo image. In gate mode the envelopes trigger the gates, and
+    /// `envelope_frame` holds the bursts instead.
+    pub fn follow_frame(&mut self, frame: impl Iterator<Item = f32>) {
+        let vocoder = self.params.env_mode.value() == EnvelopeMode::Vocoder;
+        for ((channel, envelope), sample) in self
+            .channels
+            .iter_mut()
+            .zip(self.envelope_frame.iter_mut())
+            .zip(frame)
+        {
+            *envelope = channel.envelope_follower.process(sample);
+            if vocoder {
+                channel.vocoder.analyze(sample);
+            }
+        }
+
+        let loudest = self.envelope_frame.iter().copied().fold(0.0, f32::max);
+        self.input_level.store(loudest, Ordering::Relaxed);
+        if self.params.link_envelopes.value() {
+            self.envelope_frame.fill(loudest);
+        }
+
+        if self.params.env_mode.value() == EnvelopeMode::Gate {
+            let threshold = util::db_to_gain(self.params.gate_threshold.value());
+            let trigger = self.params.gate_trigger.value();
+            for (channel, envelope) in self.channels.iter_mut().zip(self.envelope_frame.iter_mut())
+            {
+                *envelope = channel.gate.process(*envelope, threshold, trigger);
+            }
+        }
+    }
+
+    /// Applies the envelope gain, curve, floor and depth, in that order. The
+    /// scaled envelope is capped at 1, so loud input gives a steady noise
+    /// level.
+    fn shape_envelope(&self, envelope: f32) -> f32 {
+        let scaled = (envelope * util::db_to_gain(self.params.env_gain.value())).min(1.0);
+        let curved = scaled.powf(self.params.env_curve.value());
+        let floor = self.params.env_floor.value();
+        let floored = floor + (1.0 - floor) * curved;
+        // no depth at all is the same as continuous mode
+        let depth = self.params.env_depth.value();
+        1.0 - depth + depth * floored
     }
 
-    pub fn process(&mut self, sample: f32) -> f32 {
-        let noise_sample = self.next();
-        let filtered_noise = self.filter_noise(noise_sample);
+    /// Lookahead in samples at the given sample rate
+    pub fn lookahead_samples(&self, sample_rate: f32) -> usize {
+        (self.params.lookahead.value() / 1_000.0 * sample_rate).round() as usize
+    }
+
+    pub fn set_lookahead(&mut self, samples: usize) {
+        self.lookahead = samples;
+        for channel in self.channels.iter_mut() {
+            channel.dry_delay.set_delay(samples);
+        }
+    }
+
+    pub fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        // the envelope follower already heard this sample, so the dry signal
+        // is what gets delayed
+        let sample = self.channels[channel].dry_delay.process(sample);
+        let noise_sample = self.noise_frame[channel];
+        let filtered_noise = self.channels[channel].filter_noise(noise_sample);
         let mix_level = self.params.mix.value();
         let gain = self.params.gain.value();
 
@@ -85,22 +364,352 @@ impl Noise {
                 ((filtered_noise * gain) * mix_level) + (sample * (1. - mix_level))
             }
             EnvelopeMode::Follow => {
-                let envelope = self.envelope_follower.process(sample);
+                let envelope = self.shape_envelope(self.envelope_frame[channel]);
                 let noise_w_envelope = envelope * filtered_noise;
                 ((mix_level * noise_w_envelope) * gain) + (sample * (1. - mix_level))
             }
+            EnvelopeMode::Gate => {
+                let envelope = self.envelope_frame[channel];
+                let noise_w_envelope = envelope * filtered_noise;
+                ((mix_level * noise_w_envelope) * gain) + (sample * (1. - mix_level))
+            }
+            EnvelopeMode::Vocoder => {
+                let noise_w_envelope = self.channels[channel].vocoder.synthesize(filtered_noise);
+                ((mix_level * noise_w_envelope) * gain) + (sample * (1. - mix_level))
+            }
+            EnvelopeMode::Duck => {
+                // the noise starts backing off once the input passes the
+                // threshold, and is ducked by the full depth once the input
+                // is `duck_range` dB louder than that
+                let level_db = util::gain_to_db(self.envelope_frame[channel]);
+                let amount = ((level_db - self.params.duck_threshold.value())
+                    / self.params.duck_range.value())
+                .clamp(0.0, 1.0);
+                let duck_gain = 1.0 - self.params.duck_depth.value() * amount;
+                let noise_w_envelope = duck_gain * filtered_noise;
+                ((mix_level * noise_w_envelope) * gain) + (sample * (1. - mix_level))
+            }
         };
         final_sample
     }
 }
 
+/// Generator settings, read from the params once per frame and shared by
+/// every channel
+pub struct FrameSettings {
+    white: WhiteSource,
+    slope: f32,
+    velvet_density: f32,
+    crackle_density: f32,
+    crackle_size: f32,
+    crackle_hiss: f32,
+    hum_fundamental: f32,
+    hum_harmonics: usize,
+    hum_buzz: f32,
+    hum_drift: f32,
+    tape_speed: TapeSpeed,
+    tape_modulation: f32,
+    layer_levels: [(NoiseType, f32); LAYER_COUNT],
+}
+
+impl FrameSettings {
+    fn next(params: &NoiseParams) -> Self {
+        let mut layer_levels = [(NoiseType::White, 0.0); LAYER_COUNT];
+        for (layer, (noise_type, level)) in layer_levels.iter_mut().zip(params.layers.levels()) {
+            *layer = (noise_type, level.smoothed.next());
+        }
+
+        Self {
+            white: WhiteSource::from_params(params),
+            slope: params.noise_slope.smoothed.next(),
+            velvet_density: params.velvet_density.smoothed.next(),
+            crackle_density: params.crackle_density.value(),
+            crackle_size: params.crackle_size.value() / 1_000.0,
+            crackle_hiss: params.crackle_hiss.smoothed.next(),
+            hum_fundamental: match params.hum_frequency.value() {
+                HumFrequency::Fifty => 50.0,
+                HumFrequency::Sixty => 60.0,
+            },
+            hum_harmonics: params.hum_harmonics.value() as usize,
+            hum_buzz: params.hum_buzz.smoothed.next(),
+            hum_drift: params.hum_drift.value(),
+            tape_speed: params.tape_speed.value(),
+            tape_modulation: params.tape_modulation.smoothed.next(),
+            layer_levels,
+        }
+    }
+}
+
+/// Every layer's noise type, plus layers itself
+const NOISE_TYPE_COUNT: usize = LAYER_COUNT + 1;
+
+/// The noise generators, filters and envelope follower for a single channel.
+/// Every channel has its own state and RNG, so channels can be fully
+/// decorrelated and never share filter history.
+pub struct NoiseChannel {
+    pub rng: StdRng,
+    pub white: White,
+    pub pink: Pink,
+    pub brown: Brown,
+    pub violet: Violet,
+    pub slope: Slope,
+    pub blue: Blue,
+    pub grey: Grey,
+    pub velvet: Velvet,
+    pub crackle: Crackle,
+    pub hum: Hum,
+    pub tape: Tape,
+    pub lpf: FilterCascade,
+    pub hpf: FilterCascade,
+    pub tone: Biquad,
+    pub envelope_follower: EnvelopeFollower,
+    pub gate: Gate,
+    pub vocoder: Vocoder,
+    pub dry_delay: DelayLine,
+}
+
+impl NoiseChannel {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+            white: White::new(),
+            pink: Pink::new(),
+            brown: Brown::new(0.99),
+            violet: Violet::new(),
+            slope: Slope::new(sample_rate, -3.0),
+            blue: Blue::new(),
+            grey: Grey::new(sample_rate),
+            velvet: Velvet::new(sample_rate),
+            crackle: Crackle::new(sample_rate),
+            hum: Hum::new(sample_rate),
+            tape: Tape::new(sample_rate),
+            lpf: Default::default(),
+            hpf: Default::default(),
+            tone: Default::default(),
+            envelope_follower: EnvelopeFollower::new(&sample_rate),
+            gate: Gate::new(sample_rate),
+            vocoder: Vocoder::new(sample_rate),
+            dry_delay: DelayLine::new(sample_rate),
+        }
+    }
+
+    fn next(
+        &mut self,
+        active_type: NoiseType,
+        crossfade: &Crossfade,
+        settings: &FrameSettings,
+    ) -> f32 {
+        // a type that plays on both sides of a fade, like hum fading into
+        // layers with the hum layer up, is generated once and shared, so its
+        // state doesn't advance twice per sample
+        let mut generated = [None; NOISE_TYPE_COUNT];
+        let noise_sample = self.generate(active_type, settings, &mut generated);
+        match crossfade.from {
+            Some(from) => {
+                let (out_gain, in_gain) = crossfade.gains;
+                self.generate(from, settings, &mut generated) * out_gain + noise_sample * in_gain
+            }
+            None => noise_sample,
+        }
+    }
+
+    /// Returns the sample of `noise_type` already in `generated` for this
+    /// frame, or runs its generator and stores the result there
+    fn generate(
+        &mut self,
+        noise_type: NoiseType,
+        settings: &FrameSettings,
+        generated: &mut [Option<f32>; NOISE_TYPE_COUNT],
+    ) -> f32 {
+        if let Some(noise_sample) = generated[noise_type as usize] {
+            return noise_sample;
+        }
+
+        let white = &settings.white;
+        let noise_sample = match noise_type {
+            NoiseType::White => self.white.next(&mut self.rng, white),
+            NoiseType::Pink => self.pink.next(&mut self.rng, white),
+            NoiseType::Brown => self.brown.next(&mut self.rng, white),
+            NoiseType::Violet => self.violet.next(&mut self.rng, white),
+            NoiseType::Slope => self.slope.next(&mut self.rng, white),
+            NoiseType::Blue => self.blue.next(&mut self.rng, white),
+            NoiseType::Grey => self.grey.next(&mut self.rng, white),
+            NoiseType::Velvet => self.velvet.next(&mut self.rng, white),
+            NoiseType::Crackle => self.crackle.next(&mut self.rng, white),
+            NoiseType::Hum => self.hum.next(&mut self.rng, white),
+            NoiseType::Tape => self.tape.next(&mut self.rng, white),
+            NoiseType::Layers => self.generate_layers(settings, generated),
+        };
+        generated[noise_type as usize] = Some(noise_sample);
+        noise_sample
+    }
+
+    /// Sums every layer that is turned up. Muted layers are skipped, so their
+    /// generators only run while they can be heard.
+    fn generate_layers(
+        &mut self,
+        settings: &FrameSettings,
+        generated: &mut [Option<f32>; NOISE_TYPE_COUNT],
+    ) -> f32 {
+        settings
+            .layer_levels
+            .iter()
+            .filter(|(_, level)| *level > 0.0)
+            .map(|(noise_type, level)| self.generate(*noise_type, settings, generated) * level)
+            .sum()
+    }
+
+    fn filter_noise(&mut self, sample: f32) -> f32 {
+        let lowpassed_noise = self.lpf.process(sample);
+        let highpassed_noise = self.hpf.process(lowpassed_noise);
+        self.tone.process(highpassed_noise)
+    }
+
+    fn apply(&mut self, settings: &FrameSettings) {
+        self.slope.set_slope(settings.slope);
+        self.velvet.set_density(settings.velvet_density);
+        self.crackle.density = settings.crackle_density;
+        self.crackle.size = settings.crackle_size;
+        self.crackle.hiss = settings.crackle_hiss;
+        self.hum.fundamental = settings.hum_fundamental;
+        self.hum.harmonics = settings.hum_harmonics;
+        self.hum.buzz = settings.hum_buzz;
+        self.hum.drift = settings.hum_drift;
+        self.tape.set_speed(settings.tape_speed);
+        self.tape.modulation = settings.tape_modulation;
+    }
+
+    fn reset(&mut self) {
+        self.white.reset();
+        self.pink.reset();
+        self.brown.reset();
+        self.violet.reset();
+        self.slope.reset();
+        self.blue.reset();
+        self.grey.reset();
+        self.velvet.reset();
+        self.crackle.reset();
+        self.hum.reset();
+        self.tape.reset();
+        self.lpf.reset();
+        self.hpf.reset();
+        self.tone.reset();
+        self.gate.reset();
+        self.vocoder.reset();
+        self.dry_delay.reset();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.white.set_sample_rate(sample_rate);
+        self.pink.set_sample_rate(sample_rate);
+        self.brown.set_sample_rate(sample_rate);
+        self.violet.set_sample_rate(sample_rate);
+        self.slope.set_sample_rate(sample_rate);
+        self.blue.set_sample_rate(sample_rate);
+        self.grey.set_sample_rate(sample_rate);
+        self.velvet.set_sample_rate(sample_rate);
+        self.crackle.set_sample_rate(sample_rate);
+        self.hum.set_sample_rate(sample_rate);
+        self.tape.set_sample_rate(sample_rate);
+        self.envelope_follower.set_sample_rate(sample_rate);
+        self.gate.set_sample_rate(sample_rate);
+        self.vocoder.set_sample_rate(sample_rate);
+        self.dry_delay = DelayLine::new(sample_rate);
+    }
+}
+
+/// Draws the white noise every colored generator is built from, using the
+/// distribution selected in [`NoiseParams`].
+pub struct WhiteSource {
+    distribution: WhiteDistribution,
+    sigma: f32,
+    clip: f32,
+}
+
+impl WhiteSource {
+    pub fn from_params(params: &NoiseParams) -> Self {
+        Self {
+            distribution: params.white_dist.value(),
+            sigma: params.white_sigma.value(),
+            clip: params.white_clip.value(),
+        }
+    }
+
+    pub fn sample(&self, rng: &mut StdRng) -> f32 {
+        match self.distribution {
+            WhiteDistribution::Uniform => Uniform::<f32>::new(-1.0, 1.0).sample(rng),
+            WhiteDistribution::Gaussian => {
+                let normal: f32 = StandardNormal.sample(rng);
+                (normal * self.sigma).clamp(-self.clip, self.clip)
+            }
+            // TPDF: the sum of two uniform variables spans the same [-1, 1] range
+            WhiteDistribution::Triangular => {
+                let dist = Uniform::<f32>::new(-0.5, 0.5);
+                dist.sample(rng) + dist.sample(rng)
+            }
+            // inverse CDF of a Laplace distribution with a standard deviation of sigma
+            WhiteDistribution::Laplacian => {
+                let u = Uniform::<f32>::new(-0.5, 0.5).sample(rng);
+                let scale = self.sigma * FRAC_1_SQRT_2;
+                let laplace = -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln();
+                laplace.clamp(-self.clip, self.clip)
+            }
+        }
+    }
+}
+
+const CROSSFADE_TIME_SECS: f32 = 0.02;
+
+/// Equal power crossfade from the previous noise type to the active one, so
+/// switching never jumps between generators whose state has drifted apart
+pub struct Crossfade {
+    pub from: Option<NoiseType>,
+    pub gains: (f32, f32),
+    position: usize,
+    length: usize,
+}
+
+impl Crossfade {
+    fn new(sample_rate: f32) -> Self {
+        let mut this = Self {
+            from: None,
+            gains: (0.0, 1.0),
+            position: 0,
+            length: 1,
+        };
+        this.set_sample_rate(sample_rate);
+        this
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.length = ((CROSSFADE_TIME_SECS * sample_rate) as usize).max(1);
+    }
+
+    fn start(&mut self, from: NoiseType) {
+        self.from = Some(from);
+        self.position = 0;
+    }
+
+    fn step(&mut self) {
+        if self.from.is_none() {
+            return;
+        }
+
+        if self.position >= self.length {
+            self.from = None;
+            self.gains = (0.0, 1.0);
+        } else {
+            let angle = self.position as f32 / self.length as f32 * FRAC_PI_2;
+            self.gains = (angle.cos(), angle.sin());
+            self.position += 1;
+        }
+    }
+}
+
 pub trait NoiseConfig {
     fn reset(&mut self);
-    fn next(&mut self, rng: &mut StdRng) -> f32;
-    fn white(&mut self, rng: &mut StdRng) -> f32 {
-        let dist = Uniform::<f32>::new(-1.0, 1.0);
-        dist.sample(rng).clamp(-1.0, 1.0)
-    }
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32;
+    fn set_sample_rate(&mut self, _sample_rate: f32) {}
 }
 
 pub struct White;
@@ -114,8 +723,8 @@ impl White {
 impl NoiseConfig for White {
     fn reset(&mut self) {}
 
-    fn next(&mut self, rng: &mut StdRng) -> f32 {
-        return self.white(rng);
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        return white.sample(rng);
     }
 }
 
@@ -149,8 +758,8 @@ impl NoiseConfig for Pink {
         let _ = mem::replace(self, Pink::new());
     }
 
-    fn next(&mut self, rng: &mut StdRng) -> f32 {
-        let white = self.white(rng);
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let white = white.sample(rng);
         self.b0 = 0.99886 * self.b0 + white * 0.0555179;
         self.b1 = 0.99332 * self.b1 + white * 0.0750759;
         self.b2 = 0.96900 * self.b2 + white * 0.1538520;
@@ -186,8 +795,8 @@ impl NoiseConfig for Brown {
         let _ = mem::replace(self, Brown::new(0.99));
     }
 
-    fn next(&mut self, rng: &mut StdRng) -> f32 {
-        let white = self.white(rng);
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let white = white.sample(rng);
         self.current_sample = (self.leak * self.current_sample) + (1.0 - self.leak) * white;
         self.current_sample * 10.
     }
@@ -210,10 +819,556 @@ impl NoiseConfig for Violet {
         let _ = mem::replace(self, Violet::new());
     }
 
-    fn next(&mut self, rng: &mut StdRng) -> f32 {
-        let white = self.white(rng) * 0.8;
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let white = white.sample(rng) * 0.8;
         let violet = white - self.previous_sample;
         self.previous_sample = white;
         return violet;
     }
 }
+
+/// Blue noise is to pink what violet is to brown: differentiating pink noise
+/// turns its -3 dB/oct slope into +3 dB/oct.
+pub struct Blue {
+    pink: Pink,
+    previous_sample: f32,
+}
+
+impl Blue {
+    fn new() -> Self {
+        Self {
+            pink: Pink::new(),
+            previous_sample: 0.0,
+        }
+    }
+}
+
+impl NoiseConfig for Blue {
+    fn reset(&mut self) {
+        let _ = mem::replace(self, Blue::new());
+    }
+
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let pink = self.pink.next(rng, white);
+        let blue = pink - self.previous_sample;
+        self.previous_sample = pink;
+        blue * 1.5
+    }
+}
+
+/// Velvet noise places a single +1 or -1 impulse at a random position within
+/// every grid period, where the period length follows from the density.
+pub struct Velvet {
+    sample_rate: f32,
+    period: f32,
+    elapsed: f32,
+    impulse_at: f32,
+    impulse: f32,
+    fired: bool,
+}
+
+impl Velvet {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            period: sample_rate / 2_000.0,
+            elapsed: 0.0,
+            impulse_at: 0.0,
+            impulse: 1.0,
+            fired: false,
+        }
+    }
+
+    pub fn set_density(&mut self, density: f32) {
+        self.period = (self.sample_rate / density).max(1.0);
+    }
+}
+
+impl NoiseConfig for Velvet {
+    fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.impulse_at = 0.0;
+        self.fired = false;
+    }
+
+    fn next(&mut self, rng: &mut StdRng, _white: &WhiteSource) -> f32 {
+        let mut out = 0.0;
+        if !self.fired && self.impulse_at < self.elapsed + 1.0 {
+            out = self.impulse;
+            self.fired = true;
+        }
+
+        self.elapsed += 1.0;
+        if self.elapsed >= self.period {
+            self.elapsed -= self.period;
+            self.impulse_at = rng.gen::<f32>() * self.period;
+            self.impulse = match rng.gen::<bool>() {
+                true => 1.0,
+                false => -1.0,
+            };
+            self.fired = false;
+        }
+        out
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.period *= sample_rate / self.sample_rate;
+        self.sample_rate = sample_rate;
+    }
+}
+
+const CRACKLE_VOICES: usize = 8;
+
+/// A single click or pop: decaying noise through a one pole lowpass, where
+/// the cutoff sets how sharp or dull it sounds
+#[derive(Default, Clone, Copy)]
+struct Click {
+    amplitude: f32,
+    decay: f32,
+    tone: f32,
+    state: f32,
+}
+
+/// Vinyl crackle: clicks arrive as a Poisson process at the set density,
+/// each with its own random level, length and brightness, on top of an
+/// optional bed of surface hiss.
+pub struct Crackle {
+    pub density: f32,
+    pub size: f32,
+    pub hiss: f32,
+    sample_rate: f32,
+    clicks: [Click; CRACKLE_VOICES],
+    next_voice: usize,
+}
+
+impl Crackle {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            density: 10.0,
+            size: 0.001,
+            hiss: 0.01,
+            sample_rate,
+            clicks: [Click::default(); CRACKLE_VOICES],
+            next_voice: 0,
+        }
+    }
+
+    fn trigger(&mut self, rng: &mut StdRng) {
+        // most clicks are faint ticks, a few are loud pops
+        let amplitude = rng.gen::<f32>().powi(3);
+        let length = self.size * rng.gen_range(0.3..1.7);
+        let cutoff = 12_000f32 * rng.gen_range(0.05f32..1.0).powi(2);
+
+        self.clicks[self.next_voice] = Click {
+            amplitude,
+            decay: (-1.0 / (length * self.sample_rate)).exp(),
+            tone: (-TAU * cutoff.min(0.45 * self.sample_rate) / self.sample_rate).exp(),
+            state: 0.0,
+        };
+        self.next_voice = (self.next_voice + 1) % CRACKLE_VOICES;
+    }
+}
+
+impl NoiseConfig for Crackle {
+    fn reset(&mut self) {
+        self.clicks = [Click::default(); CRACKLE_VOICES];
+        self.next_voice = 0;
+    }
+
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        if rng.gen::<f32>() < self.density / self.sample_rate {
+            self.trigger(rng);
+        }
+
+        let excitation = white.sample(rng);
+        let clicks: f32 = self
+            .clicks
+            .iter_mut()
+            .map(|click| {
+                click.state = click.tone * click.state + (1.0 - click.tone) * excitation;
+                click.amplitude *= click.decay;
+                click.state * click.amplitude
+            })
+            .sum();
+
+        clicks * 4.0 + white.sample(rng) * self.hiss
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+}
+
+// how far the drift can pull the pitch and level away from nominal
+const HUM_PITCH_DRIFT: f32 = 0.005;
+const HUM_LEVEL_DRIFT: f32 = 0.3;
+const HUM_DRIFT_TIME_SECS: f32 = 0.5;
+
+/// Mains hum: a 50 or 60 Hz fundamental with a 1/k harmonic series, which
+/// can be blended into a full wave rectified buzz. Pitch and level wander
+/// slowly along two lowpassed random walks.
+pub struct Hum {
+    pub fundamental: f32,
+    pub harmonics: usize,
+    pub buzz: f32,
+    pub drift: f32,
+    sample_rate: f32,
+    phase: f32,
+    pitch_drift: f32,
+    level_drift: f32,
+    drift_coefficient: f32,
+    drift_norm: f32,
+}
+
+impl Hum {
+    fn new(sample_rate: f32) -> Self {
+        let mut this = Self {
+            fundamental: 60.0,
+            harmonics: 6,
+            buzz: 0.0,
+            drift: 0.2,
+            sample_rate,
+            phase: 0.0,
+            pitch_drift: 0.0,
+            level_drift: 0.0,
+            drift_coefficient: 0.0,
+            drift_norm: 1.0,
+        };
+        this.set_sample_rate(sample_rate);
+        this
+    }
+}
+
+impl NoiseConfig for Hum {
+    fn reset(&mut self) {
+        self.phase = 0.0;
+        self.pitch_drift = 0.0;
+        self.level_drift = 0.0;
+    }
+
+    fn next(&mut self, rng: &mut StdRng, _white: &WhiteSource) -> f32 {
+        let a = self.drift_coefficient;
+        self.pitch_drift = a * self.pitch_drift + (1.0 - a) * rng.gen_range(-1.0..1.0);
+        self.level_drift = a * self.level_drift + (1.0 - a) * rng.gen_range(-1.0..1.0);
+
+        let pitch = 1.0 + HUM_PITCH_DRIFT * self.drift * self.pitch_drift * self.drift_norm;
+        let level = 1.0 + HUM_LEVEL_DRIFT * self.drift * self.level_drift * self.drift_norm;
+
+        self.phase += self.fundamental * pitch / self.sample_rate;
+        self.phase -= self.phase.floor();
+
+        let angle = TAU * self.phase;
+        let (series, norm) = (1..=self.harmonics).fold((0.0, 0.0), |(sum, norm), k| {
+            let k = k as f32;
+            (sum + (k * angle).sin() / k, norm + 1.0 / (k * k))
+        });
+        // both shapes are brought to the RMS level of a plain sine
+        let series = series / norm.sqrt();
+        let rectified = (angle.sin().abs() - 2.0 / PI) * 2.3;
+
+        let hum = (1.0 - self.buzz) * series + self.buzz * rectified;
+        hum * level.max(0.0) * 0.5
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.drift_coefficient = (-1.0 / (HUM_DRIFT_TIME_SECS * sample_rate)).exp();
+        // a one pole lowpass shrinks the variance of its input by (1 - a) / (1 + a),
+        // this scales the uniform random walk back up to unit variance
+        let a = self.drift_coefficient;
+        self.drift_norm = ((1.0 + a) / (1.0 - a)).sqrt() * 3f32.sqrt();
+    }
+}
+
+/// Tape hiss: white noise with the high frequency lift and top end rolloff
+/// of a tape machine at the selected speed, plus modulation noise that rises
+/// and falls with the level of the signal being "recorded".
+pub struct Tape {
+    pub modulation: f32,
+    speed: TapeSpeed,
+    sample_rate: f32,
+    lift: Biquad,
+    rolloff: Biquad,
+    level: f32,
+    envelope_follower: EnvelopeFollower,
+    input_level: f32,
+}
+
+impl Tape {
+    fn new(sample_rate: f32) -> Self {
+        let mut this = Self {
+            modulation: 0.5,
+            speed: TapeSpeed::Medium,
+            sample_rate,
+            lift: Biquad::default(),
+            rolloff: Biquad::default(),
+            level: 1.0,
+            envelope_follower: EnvelopeFollower::new(&sample_rate),
+            input_level: 0.0,
+        };
+        this.update_coefficients();
+        this
+    }
+
+    pub fn set_speed(&mut self, speed: TapeSpeed) {
+        if speed != self.speed {
+            self.speed = speed;
+            self.update_coefficients();
+        }
+    }
+
+    /// Tracks the level of the incoming audio for the modulation noise
+    pub fn follow(&mut self, sample: f32) {
+        self.input_level = self.envelope_follower.process(sample);
+    }
+
+    fn update_coefficients(&mut self) {
+        // (lift zero, lift pole, rolloff, level), slower tape is both noisier
+        // and darker. The lift passes high frequencies at unity and cuts the
+        // lows by the ratio between its zero and pole.
+        let (zero_hz, pole_hz, rolloff_hz, level) = match self.speed {
+            TapeSpeed::Slow => (1_500.0, 6_000.0, 10_000.0, 1.0),
+            TapeSpeed::Medium => (3_000.0, 10_000.0, 15_000.0, 0.7),
+            TapeSpeed::Fast => (5_000.0, 16_000.0, 20_000.0, 0.5),
+        };
+        let sample_rate = self.sample_rate;
+        let warp =
+            |freq: f32| 2.0 * sample_rate * (PI * freq.min(0.45 * sample_rate) / sample_rate).tan();
+        let (wz, wp, wc) = (warp(zero_hz), warp(pole_hz), warp(rolloff_hz));
+
+        self.lift
+            .coefficients
+            .bilinear([0.0, 1.0, wz], [0.0, 1.0, wp], sample_rate);
+        self.rolloff.coefficients.bilinear(
+            [0.0, 0.0, wc * wc],
+            [1.0, wc / FRAC_1_SQRT_2, wc * wc],
+            sample_rate,
+        );
+        self.level = level;
+    }
+}
+
+impl NoiseConfig for Tape {
+    fn reset(&mut self) {
+        self.lift.reset();
+        self.rolloff.reset();
+    }
+
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let hiss = self.rolloff.process(self.lift.process(white.sample(rng)));
+        let modulation_noise = white.sample(rng) * self.input_level * self.modulation;
+        hiss * self.level + modulation_noise
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.envelope_follower.set_sample_rate(sample_rate);
+        self.update_coefficients();
+    }
+}
+
+/// The gain that brings white noise shaped by a filter back to the power of
+/// the unshaped noise. The filter's squared magnitude response is integrated
+/// over `points` log spaced steps from `low_hz` to Nyquist, and taken to be
+/// flat below `low_hz`.
+fn white_power_gain(
+    low_hz: f32,
+    sample_rate: f32,
+    points: usize,
+    magnitude_squared: impl Fn(f32) -> f32,
+) -> f32 {
+    let nyquist = 0.5 * sample_rate;
+    let ratio = (nyquist / low_hz).powf(1.0 / points as f32);
+    let mut power = magnitude_squared(low_hz) * low_hz;
+    let mut freq = low_hz;
+    for _ in 0..points {
+        let next_freq = freq * ratio;
+        power += magnitude_squared((freq * next_freq).sqrt()) * (next_freq - freq);
+        freq = next_freq;
+    }
+    (nyquist / power).sqrt()
+}
+
+// A-weighting pole frequencies, see IEC 61672-1
+const A_WEIGHTING_F1: f32 = 20.598997;
+const A_WEIGHTING_F2: f32 = 107.65265;
+const A_WEIGHTING_F3: f32 = 737.86223;
+const A_WEIGHTING_F4: f32 = 12194.217;
+// the inverse curve rises without bound at both ends, so it levels off below
+// this frequency and above this fraction of the sample rate
+const GREY_LOW_LIMIT_HZ: f32 = 10.0;
+const GREY_HIGH_LIMIT_RATIO: f32 = 0.3;
+const GREY_POWER_POINTS: usize = 64;
+
+/// Grey noise is white noise shaped by an inverse A-weighting curve, so it
+/// sounds roughly equally loud across the spectrum. The poles of the
+/// A-weighting curve become zeros. Its four zeros at DC become poles at
+/// 10 Hz to keep the low end bounded, and the rise above 12.2 kHz is capped
+/// by two poles at 30% of the sample rate.
+pub struct Grey {
+    sections: [Biquad; 3],
+    sample_rate: f32,
+    gain: f32,
+}
+
+impl Grey {
+    fn new(sample_rate: f32) -> Self {
+        let mut this = Self {
+            sections: [Biquad::default(), Biquad::default(), Biquad::default()],
+            sample_rate,
+            gain: 1.0,
+        };
+        this.update_coefficients();
+        this
+    }
+
+    fn update_coefficients(&mut self) {
+        let sample_rate = self.sample_rate;
+        let high_limit_hz = GREY_HIGH_LIMIT_RATIO * sample_rate;
+        let (w1, w2, w3, w4) = (
+            TAU * A_WEIGHTING_F1,
+            TAU * A_WEIGHTING_F2,
+            TAU * A_WEIGHTING_F3,
+            TAU * A_WEIGHTING_F4.min(high_limit_hz),
+        );
+        let w0 = TAU * GREY_LOW_LIMIT_HZ;
+        // the cap sits close enough to Nyquist to need prewarping
+        let wc = 2.0 * sample_rate * (PI * high_limit_hz / sample_rate).tan();
+        let low_limit = [1.0, 2.0 * w0, w0 * w0];
+
+        self.sections[0]
+            .coefficients
+            .bilinear([1.0, 2.0 * w1, w1 * w1], low_limit, sample_rate);
+        self.sections[1]
+            .coefficients
+            .bilinear([1.0, w2 + w3, w2 * w3], low_limit, sample_rate);
+        self.sections[2].coefficients.bilinear(
+            [1.0, 2.0 * w4, w4 * w4],
+            [1.0, 2.0 * wc, wc * wc],
+            sample_rate,
+        );
+
+        // the boosted low end would otherwise be far louder than white noise
+        self.gain = white_power_gain(GREY_LOW_LIMIT_HZ, sample_rate, GREY_POWER_POINTS, |freq| {
+            self.magnitude_squared(freq)
+        });
+    }
+
+    fn magnitude_squared(&self, freq: f32) -> f32 {
+        self.sections
+            .iter()
+            .map(|section| {
+                section
+                    .coefficients
+                    .magnitude(freq, self.sample_rate)
+                    .powi(2)
+            })
+            .product()
+    }
+}
+
+impl NoiseConfig for Grey {
+    fn reset(&mut self) {
+        self.sections.iter_mut().for_each(Biquad::reset);
+    }
+
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let white = white.sample(rng);
+        let shaped = self
+            .sections
+            .iter_mut()
+            .fold(white, |sample, section| section.process(sample));
+        shaped * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficients();
+    }
+}
+
+const SLOPE_SECTIONS: usize = 12;
+const SLOPE_LOW_HZ: f32 = 10.0;
+const SLOPE_HIGH_HZ: f32 = 20_000.0;
+const SLOPE_RESOLUTION: f32 = 0.05;
+const SLOPE_POWER_POINTS: usize = 32;
+
+/// Noise with an arbitrary 1/f^alpha spectrum, set as a slope in dB/oct.
+/// White noise goes through a bank of first-order shelves whose poles are
+/// spread logarithmically between 10 Hz and 20 kHz. Moving each zero away
+/// from its pole by a fraction of the spacing between poles tilts the
+/// spectrum by the same fraction of 6 dB/oct.
+pub struct Slope {
+    sections: [ShelfSection; SLOPE_SECTIONS],
+    slope: f32,
+    sample_rate: f32,
+    gain: f32,
+}
+
+impl Slope {
+    fn new(sample_rate: f32, slope: f32) -> Self {
+        let mut this = Self {
+            sections: [ShelfSection::default(); SLOPE_SECTIONS],
+            slope,
+            sample_rate,
+            gain: 1.0,
+        };
+        this.update_coefficients();
+        this
+    }
+
+    /// Coefficients are only recalculated once the slope has moved by an
+    /// audible amount, since smoothing would otherwise do so every sample.
+    pub fn set_slope(&mut self, slope: f32) {
+        if (slope - self.slope).abs() >= SLOPE_RESOLUTION {
+            self.slope = slope;
+            self.update_coefficients();
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        let spacing = (SLOPE_HIGH_HZ / SLOPE_LOW_HZ).log2() / SLOPE_SECTIONS as f32;
+        let offset = 2f32.powf(spacing * self.slope.abs() / 6.0);
+
+        for (idx, section) in self.sections.iter_mut().enumerate() {
+            let corner = SLOPE_LOW_HZ * 2f32.powf(spacing * idx as f32);
+            match self.slope < 0.0 {
+                true => section.update(corner * offset, corner, self.sample_rate),
+                false => section.update(corner, corner * offset, self.sample_rate),
+            }
+        }
+
+        // keeps the level put while the slope is swept
+        self.gain = white_power_gain(SLOPE_LOW_HZ, self.sample_rate, SLOPE_POWER_POINTS, |freq| {
+            self.magnitude_squared(freq)
+        });
+    }
+
+    fn magnitude_squared(&self, freq: f32) -> f32 {
+        let (sin, cos) = (TAU * freq / self.sample_rate).sin_cos();
+        self.sections
+            .iter()
+            .map(|section| section.magnitude_squared(cos, sin))
+            .product()
+    }
+}
+
+impl NoiseConfig for Slope {
+    fn reset(&mut self) {
+        self.sections.iter_mut().for_each(ShelfSection::reset);
+    }
+
+    fn next(&mut self, rng: &mut StdRng, white: &WhiteSource) -> f32 {
+        let white = white.sample(rng);
+        let shaped = self
+            .sections
+            .iter_mut()
+            .fold(white, |sample, section| section.process(sample));
+        shaped * self.gain
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coefficients();
+    }
+}
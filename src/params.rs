@@ -1,15 +1,19 @@
 use nih_plug::prelude::{
-    formatters, util, Enum, EnumParam, FloatParam, FloatRange, Params, SmoothingStyle,
+    formatters, util, BoolParam, Enum, EnumParam, FloatParam, FloatRange, IntParam, IntRange,
+    Params, SmoothingStyle,
 };
 use nih_plug_vizia::ViziaState;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    f32::consts::FRAC_1_SQRT_2,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use crate::{editor, envelope};
 
-#[derive(Enum, PartialEq, Debug)]
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
 pub enum NoiseType {
     #[id = "white"]
     White,
@@ -19,6 +23,193 @@ pub enum NoiseType {
     Brown,
     #[id = "violet"]
     Violet,
+    #[id = "slope"]
+    Slope,
+    #[id = "blue"]
+    Blue,
+    #[id = "grey"]
+    Grey,
+    #[id = "velvet"]
+    Velvet,
+    #[id = "crackle"]
+    Crackle,
+    #[id = "hum"]
+    Hum,
+    #[id = "tape"]
+    Tape,
+    #[id = "layers"]
+    Layers,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum WhiteDistribution {
+    #[id = "uniform"]
+    Uniform,
+    #[id = "gaussian"]
+    Gaussian,
+    #[id = "triangular"]
+    Triangular,
+    #[id = "laplacian"]
+    Laplacian,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum HumFrequency {
+    #[id = "50hz"]
+    #[name = "50 Hz"]
+    Fifty,
+    #[id = "60hz"]
+    #[name = "60 Hz"]
+    Sixty,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum TapeSpeed {
+    #[id = "7.5ips"]
+    #[name = "7.5 ips"]
+    Slow,
+    #[id = "15ips"]
+    #[name = "15 ips"]
+    Medium,
+    #[id = "30ips"]
+    #[name = "30 ips"]
+    Fast,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum StereoMode {
+    #[id = "mono"]
+    Mono,
+    #[id = "decorrelated"]
+    Decorrelated,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum FilterSlope {
+    #[id = "12db"]
+    #[name = "12 dB/oct"]
+    Twelve,
+    #[id = "24db"]
+    #[name = "24 dB/oct"]
+    TwentyFour,
+    #[id = "36db"]
+    #[name = "36 dB/oct"]
+    ThirtySix,
+    #[id = "48db"]
+    #[name = "48 dB/oct"]
+    FortyEight,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum FilterResponse {
+    #[id = "butterworth"]
+    Butterworth,
+    #[id = "linkwitz-riley"]
+    #[name = "Linkwitz-Riley"]
+    LinkwitzRiley,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy)]
+pub enum ToneType {
+    #[id = "peak"]
+    Peak,
+    #[id = "low-shelf"]
+    #[name = "Low Shelf"]
+    LowShelf,
+    #[id = "high-shelf"]
+    #[name = "High Shelf"]
+    HighShelf,
+    #[id = "bandpass"]
+    Bandpass,
+    #[id = "notch"]
+    Notch,
+}
+
+#[derive(Enum, PartialEq, Debug, Clone, Copy, Default)]
+pub enum FilterEngine {
+    #[default]
+    #[id = "biquad"]
+    Biquad,
+    #[id = "state-variable"]
+    #[name = "State Variable"]
+    StateVariable,
+}
+
+/// Range of the LPF and HPF cutoffs. The DSP also keeps them below Nyquist.
+pub const MIN_CUTOFF: f32 = 10.0;
+pub const MAX_CUTOFF: f32 = 22_000.0;
+
+pub const LAYER_COUNT: usize = 11;
+
+/// Per noise type levels for the layered mode, where every layer with a
+/// level above zero is summed
+#[derive(Params)]
+pub struct LayerParams {
+    #[id = "layer-white"]
+    pub white: FloatParam,
+    #[id = "layer-pink"]
+    pub pink: FloatParam,
+    #[id = "layer-brown"]
+    pub brown: FloatParam,
+    #[id = "layer-violet"]
+    pub violet: FloatParam,
+    #[id = "layer-slope"]
+    pub slope: FloatParam,
+    #[id = "layer-blue"]
+    pub blue: FloatParam,
+    #[id = "layer-grey"]
+    pub grey: FloatParam,
+    #[id = "layer-velvet"]
+    pub velvet: FloatParam,
+    #[id = "layer-crackle"]
+    pub crackle: FloatParam,
+    #[id = "layer-hum"]
+    pub hum: FloatParam,
+    #[id = "layer-tape"]
+    pub tape: FloatParam,
+}
+
+impl LayerParams {
+    fn new() -> Self {
+        Self {
+            white: Self::level_param("White Level", 1.0),
+            pink: Self::level_param("Pink Level", 0.0),
+            brown: Self::level_param("Brown Level", 0.0),
+            violet: Self::level_param("Violet Level", 0.0),
+            slope: Self::level_param("Slope Level", 0.0),
+            blue: Self::level_param("Blue Level", 0.0),
+            grey: Self::level_param("Grey Level", 0.0),
+            velvet: Self::level_param("Velvet Level", 0.0),
+            crackle: Self::level_param("Crackle Level", 0.0),
+            hum: Self::level_param("Hum Level", 0.0),
+            tape: Self::level_param("Tape Level", 0.0),
+        }
+    }
+
+    fn level_param(name: &str, default: f32) -> FloatParam {
+        FloatParam::new(name, default, FloatRange::Linear { min: 0.0, max: 1.0 })
+            .with_unit("%")
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage())
+    }
+
+    /// Every layer's noise type paired with its level
+    pub fn levels(&self) -> [(NoiseType, &FloatParam); LAYER_COUNT] {
+        [
+            (NoiseType::White, &self.white),
+            (NoiseType::Pink, &self.pink),
+            (NoiseType::Brown, &self.brown),
+            (NoiseType::Violet, &self.violet),
+            (NoiseType::Slope, &self.slope),
+            (NoiseType::Blue, &self.blue),
+            (NoiseType::Grey, &self.grey),
+            (NoiseType::Velvet, &self.velvet),
+            (NoiseType::Crackle, &self.crackle),
+            (NoiseType::Hum, &self.hum),
+            (NoiseType::Tape, &self.tape),
+        ]
+    }
 }
 
 #[derive(Params)]
@@ -29,18 +220,125 @@ pub struct NoiseParams {
     pub gain: FloatParam,
     #[id = "noise-type"]
     pub noise_type: EnumParam<NoiseType>,
+    #[id = "noise-slope"]
+    pub noise_slope: FloatParam,
+    #[id = "velvet-density"]
+    pub velvet_density: FloatParam,
+    #[id = "crackle-density"]
+    pub crackle_density: FloatParam,
+    #[id = "crackle-size"]
+    pub crackle_size: FloatParam,
+    #[id = "crackle-hiss"]
+    pub crackle_hiss: FloatParam,
+    #[id = "hum-frequency"]
+    pub hum_frequency: EnumParam<HumFrequency>,
+    #[id = "hum-harmonics"]
+    pub hum_harmonics: IntParam,
+    #[id = "hum-buzz"]
+    pub hum_buzz: FloatParam,
+    #[id = "hum-drift"]
+    pub hum_drift: FloatParam,
+    #[id = "tape-speed"]
+    pub tape_speed: EnumParam<TapeSpeed>,
+    #[id = "tape-modulation"]
+    pub tape_modulation: FloatParam,
+    #[id = "white-distribution"]
+    pub white_dist: EnumParam<WhiteDistribution>,
+    #[id = "white-sigma"]
+    pub white_sigma: FloatParam,
+    #[id = "white-clip"]
+    pub white_clip: FloatParam,
     #[id = "mix"]
     pub mix: FloatParam,
     #[id = "highpass-frequency-cutoff"]
     pub hpf_fc: FloatParam,
     #[id = "lowpass-frequency-cutoff"]
     pub lpf_fc: FloatParam,
+    #[id = "filter-link"]
+    pub filter_link: BoolParam,
+    #[id = "filter-center"]
+    pub filter_center: FloatParam,
+    #[id = "filter-width"]
+    pub filter_width: FloatParam,
+    #[id = "highpass-q"]
+    pub hpf_q: FloatParam,
+    #[id = "lowpass-q"]
+    pub lpf_q: FloatParam,
+    #[id = "tone-type"]
+    pub tone_type: EnumParam<ToneType>,
+    #[id = "tone-frequency"]
+    pub tone_fc: FloatParam,
+    #[id = "tone-gain"]
+    pub tone_gain: FloatParam,
+    #[id = "tone-q"]
+    pub tone_q: FloatParam,
+    #[id = "filter-slope"]
+    pub filter_slope: EnumParam<FilterSlope>,
+    #[id = "filter-response"]
+    pub filter_response: EnumParam<FilterResponse>,
+    #[id = "filter-engine"]
+    pub filter_engine: EnumParam<FilterEngine>,
+    #[id = "stereo-mode"]
+    pub stereo_mode: EnumParam<StereoMode>,
+    #[id = "stereo-width"]
+    pub stereo_width: FloatParam,
+    #[nested(group = "Layers")]
+    pub layers: LayerParams,
     #[id = "envelope-mode"]
     pub env_mode: EnumParam<envelope::follower::EnvelopeMode>,
+    #[id = "envelope-depth"]
+    pub env_depth: FloatParam,
+    #[id = "envelope-curve"]
+    pub env_curve: FloatParam,
+    #[id = "envelope-floor"]
+    pub env_floor: FloatParam,
+    #[id = "envelope-gain"]
+    pub env_gain: FloatParam,
+    #[id = "duck-depth"]
+    pub duck_depth: FloatParam,
+    #[id = "duck-threshold"]
+    pub duck_threshold: FloatParam,
+    #[id = "duck-range"]
+    pub duck_range: FloatParam,
+    #[id = "gate-trigger"]
+    pub gate_trigger: EnumParam<envelope::gate::GateTrigger>,
+    #[id = "gate-threshold"]
+    pub gate_threshold: FloatParam,
+    #[id = "gate-attack"]
+    pub gate_attack: FloatParam,
+    #[id = "gate-hold"]
+    pub gate_hold: FloatParam,
+    #[id = "gate-decay"]
+    pub gate_decay: FloatParam,
+    #[id = "vocoder-bands"]
+    pub vocoder_bands: IntParam,
+    #[id = "vocoder-low"]
+    pub vocoder_low: FloatParam,
+    #[id = "vocoder-high"]
+    pub vocoder_high: FloatParam,
+    #[id = "lookahead"]
+    pub lookahead: FloatParam,
+    #[id = "envelope-source"]
+    pub env_source: EnumParam<envelope::follower::EnvelopeSource>,
+    #[id = "link-envelopes"]
+    pub link_envelopes: BoolParam,
+    #[id = "envelope-attack"]
+    pub env_attack: FloatParam,
+    #[id = "envelope-hold"]
+    pub env_hold: FloatParam,
+    #[id = "envelope-release"]
+    pub env_release: FloatParam,
+    #[id = "envelope-detector"]
+    pub env_detector: EnumParam<envelope::follower::Detector>,
+    #[id = "envelope-rms-window"]
+    pub env_rms_window: FloatParam,
 }
 
 impl NoiseParams {
-    pub fn new(should_update_filters: Arc<AtomicBool>) -> Self {
+    pub fn new(
+        should_update_filters: Arc<AtomicBool>,
+        should_update_envelopes: Arc<AtomicBool>,
+    ) -> Self {
         Self {
             editor_state: editor::default_state(),
             gain: FloatParam::new(
@@ -57,34 +355,153 @@ impl NoiseParams {
             .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
             .with_string_to_value(formatters::s2v_f32_gain_to_db()),
             noise_type: EnumParam::new("Noise Type", NoiseType::White),
+            noise_slope: FloatParam::new(
+                "Noise Slope",
+                -3.0,
+                FloatRange::Linear {
+                    min: -6.0,
+                    max: 6.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_unit(" dB/oct")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            velvet_density: FloatParam::new(
+                "Velvet Density",
+                2_000.0,
+                FloatRange::Skewed {
+                    min: 50.0,
+                    max: 20_000.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Logarithmic(50.0))
+            .with_unit(" imp/s")
+            .with_value_to_string(formatters::v2s_f32_rounded(0)),
+            crackle_density: FloatParam::new(
+                "Crackle Density",
+                10.0,
+                FloatRange::Skewed {
+                    min: 0.5,
+                    max: 200.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_unit(" /s")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            crackle_size: FloatParam::new(
+                "Crackle Size",
+                1.0,
+                FloatRange::Skewed {
+                    min: 0.1,
+                    max: 10.0,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+            crackle_hiss: FloatParam::new(
+                "Crackle Hiss",
+                util::db_to_gain(-40.0),
+                FloatRange::Skewed {
+                    min: util::db_to_gain(-60.0),
+                    max: util::db_to_gain(0.0),
+                    factor: FloatRange::gain_skew_factor(-60.0, 0.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Logarithmic(50.0))
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
+            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
+            hum_frequency: EnumParam::new("Hum Frequency", HumFrequency::Sixty),
+            hum_harmonics: IntParam::new("Hum Harmonics", 6, IntRange::Linear { min: 1, max: 16 }),
+            hum_buzz: FloatParam::new("Hum Buzz", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_unit("%")
+                .with_smoother(SmoothingStyle::Linear(50.0))
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+            hum_drift: FloatParam::new("Hum Drift", 0.2, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_unit("%")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+            tape_speed: EnumParam::new("Tape Speed", TapeSpeed::Medium),
+            tape_modulation: FloatParam::new(
+                "Tape Modulation",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+            white_dist: EnumParam::new("White Distribution", WhiteDistribution::Uniform),
+            white_sigma: FloatParam::new(
+                "White Sigma",
+                0.33,
+                FloatRange::Linear {
+                    min: 0.05,
+                    max: 1.0,
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+            white_clip: FloatParam::new(
+                "White Clip",
+                1.0,
+                FloatRange::Linear { min: 0.1, max: 1.0 },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
             mix: FloatParam::new("Mix", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
                 .with_unit("%")
                 .with_smoother(SmoothingStyle::Linear(10.0))
                 .with_value_to_string(formatters::v2s_f32_percentage(0))
                 .with_string_to_value(formatters::s2v_f32_percentage()),
-            hpf_fc: FloatParam::new(
+            hpf_fc: Self::cutoff_param(
                 "Highpass Freq Cutoff",
-                5.,
-                FloatRange::Skewed {
-                    min: 5.,
-                    max: 5_000.,
-                    factor: FloatRange::skew_factor(-1.0),
+                MIN_CUTOFF,
+                should_update_filters.clone(),
+            ),
+            lpf_fc: Self::cutoff_param(
+                "Lowpass Freq Cutoff",
+                MAX_CUTOFF,
+                should_update_filters.clone(),
+            ),
+            filter_link: BoolParam::new("Link Filters", false).with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            filter_center: Self::cutoff_param(
+                "Filter Center",
+                1_000.,
+                should_update_filters.clone(),
+            ),
+            filter_width: FloatParam::new(
+                "Filter Width",
+                2.0,
+                FloatRange::Linear {
+                    min: 0.1,
+                    max: 10.0,
                 },
             )
-            .with_smoother(SmoothingStyle::Logarithmic(100.0))
-            .with_value_to_string(formatters::v2s_f32_hz_then_khz(0))
-            .with_string_to_value(formatters::s2v_f32_hz_then_khz())
+            .with_unit(" oct")
+            .with_smoother(SmoothingStyle::Linear(100.0))
+            .with_value_to_string(formatters::v2s_f32_rounded(1))
             .with_callback({
                 let should_update_filters = should_update_filters.clone();
                 Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
             }),
-            lpf_fc: FloatParam::new(
-                "Lowpass Freq Cutoff",
-                20_000.,
+            hpf_q: Self::filter_q_param("Highpass Q", should_update_filters.clone()),
+            lpf_q: Self::filter_q_param("Lowpass Q", should_update_filters.clone()),
+            tone_type: EnumParam::new("Tone Type", ToneType::Peak).with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            tone_fc: FloatParam::new(
+                "Tone Frequency",
+                1_000.,
                 FloatRange::Skewed {
-                    min: 5_000.,
+                    min: 20.,
                     max: 20_000.,
-                    factor: FloatRange::skew_factor(1.0),
+                    factor: FloatRange::skew_factor(-2.0),
                 },
             )
             .with_smoother(SmoothingStyle::Logarithmic(100.0))
@@ -94,7 +511,270 @@ impl NoiseParams {
                 let should_update_filters = should_update_filters.clone();
                 Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
             }),
+            tone_gain: FloatParam::new(
+                "Tone Gain",
+                0.0,
+                FloatRange::Linear {
+                    min: -24.0,
+                    max: 24.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_value_to_string(formatters::v2s_f32_rounded(1))
+            .with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            tone_q: Self::filter_q_param("Tone Q", should_update_filters.clone()),
+            filter_slope: EnumParam::new("Filter Slope", FilterSlope::Twelve).with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            filter_response: EnumParam::new("Filter Response", FilterResponse::Butterworth)
+                .with_callback({
+                    let should_update_filters = should_update_filters.clone();
+                    Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+                }),
+            filter_engine: EnumParam::new("Filter Engine", FilterEngine::Biquad).with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            stereo_mode: EnumParam::new("Stereo Mode", StereoMode::Decorrelated),
+            stereo_width: FloatParam::new(
+                "Stereo Width",
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_unit("%")
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+            layers: LayerParams::new(),
             env_mode: EnumParam::new("Envelope Mode", envelope::follower::EnvelopeMode::Follow),
+            env_depth: FloatParam::new(
+                "Envelope Depth",
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+            env_curve: FloatParam::new(
+                "Envelope Curve",
+                1.0,
+                FloatRange::Skewed {
+                    min: 0.25,
+                    max: 4.0,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+            env_floor: FloatParam::new(
+                "Envelope Floor",
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+            env_gain: FloatParam::new(
+                "Envelope Gain",
+                0.0,
+                FloatRange::Linear {
+                    min: -24.0,
+                    max: 24.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            duck_depth: FloatParam::new(
+                "Duck Depth",
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+            duck_threshold: FloatParam::new(
+                "Duck Threshold",
+                -40.0,
+                FloatRange::Linear {
+                    min: -60.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            duck_range: FloatParam::new(
+                "Duck Range",
+                20.0,
+                FloatRange::Linear {
+                    min: 1.0,
+                    max: 40.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            gate_trigger: EnumParam::new("Gate Trigger", envelope::gate::GateTrigger::Threshold),
+            gate_threshold: FloatParam::new(
+                "Gate Threshold",
+                -24.0,
+                FloatRange::Linear {
+                    min: -60.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            gate_attack: Self::envelope_time_param(
+                "Gate Attack",
+                1.0,
+                0.1,
+                100.0,
+                should_update_envelopes.clone(),
+            ),
+            gate_hold: Self::envelope_time_param(
+                "Gate Hold",
+                50.0,
+                0.0,
+                1_000.0,
+                should_update_envelopes.clone(),
+            ),
+            gate_decay: Self::envelope_time_param(
+                "Gate Decay",
+                150.0,
+                1.0,
+                2_000.0,
+                should_update_envelopes.clone(),
+            ),
+            vocoder_bands: IntParam::new(
+                "Vocoder Bands",
+                8,
+                IntRange::Linear {
+                    min: 2,
+                    max: envelope::vocoder::MAX_VOCODER_BANDS as i32,
+                },
+            )
+            .with_callback({
+                let should_update_filters = should_update_filters.clone();
+                Arc::new(move |_| should_update_filters.store(true, Ordering::Relaxed))
+            }),
+            vocoder_low: Self::cutoff_param("Vocoder Low", 100., should_update_filters.clone()),
+            vocoder_high: Self::cutoff_param("Vocoder High", 8_000., should_update_filters.clone()),
+            lookahead: FloatParam::new(
+                "Lookahead",
+                0.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: envelope::lookahead::MAX_LOOKAHEAD.as_secs_f32() * 1_000.0,
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(1))
+            // every change is reported to the host as new latency
+            .non_automatable(),
+            env_source: EnumParam::new("Envelope Source", envelope::follower::EnvelopeSource::Main),
+            link_envelopes: BoolParam::new("Link Envelopes", false),
+            env_attack: Self::envelope_time_param(
+                "Envelope Attack",
+                10.0,
+                0.1,
+                500.0,
+                should_update_envelopes.clone(),
+            ),
+            env_hold: Self::envelope_time_param(
+                "Envelope Hold",
+                0.0,
+                0.0,
+                1_000.0,
+                should_update_envelopes.clone(),
+            ),
+            env_release: Self::envelope_time_param(
+                "Envelope Release",
+                100.0,
+                1.0,
+                5_000.0,
+                should_update_envelopes.clone(),
+            ),
+            env_detector: EnumParam::new("Envelope Detector", envelope::follower::Detector::Peak)
+                .with_callback({
+                    let should_update_envelopes = should_update_envelopes.clone();
+                    Arc::new(move |_| should_update_envelopes.store(true, Ordering::Relaxed))
+                }),
+            env_rms_window: Self::envelope_time_param(
+                "RMS Window",
+                50.0,
+                1.0,
+                envelope::follower::MAX_RMS_WINDOW.as_secs_f32() * 1_000.0,
+                should_update_envelopes,
+            ),
         }
     }
+
+    /// A time in milliseconds for the envelope follower or gate
+    fn envelope_time_param(
+        name: &str,
+        default: f32,
+        min: f32,
+        max: f32,
+        should_update_envelopes: Arc<AtomicBool>,
+    ) -> FloatParam {
+        FloatParam::new(
+            name,
+            default,
+            FloatRange::Skewed {
+                min,
+                max,
+                factor: FloatRange::skew_factor(-2.0),
+            },
+        )
+        .with_unit(" ms")
+        .with_value_to_string(formatters::v2s_f32_rounded(1))
+        .with_callback(Arc::new(move |_| {
+            should_update_envelopes.store(true, Ordering::Relaxed)
+        }))
+    }
+
+    /// Cutoff of the LPF or HPF, the center of the band they form when
+    /// linked, or an edge of the vocoder bands
+    fn cutoff_param(
+        name: &str,
+        default: f32,
+        should_update_filters: Arc<AtomicBool>,
+    ) -> FloatParam {
+        FloatParam::new(
+            name,
+            default,
+            FloatRange::Skewed {
+                min: MIN_CUTOFF,
+                max: MAX_CUTOFF,
+                factor: FloatRange::skew_factor(-2.0),
+            },
+        )
+        .with_smoother(SmoothingStyle::Logarithmic(100.0))
+        .with_value_to_string(formatters::v2s_f32_hz_then_khz(0))
+        .with_string_to_value(formatters::s2v_f32_hz_then_khz())
+        .with_callback(Arc::new(move |_| {
+            should_update_filters.store(true, Ordering::Relaxed)
+        }))
+    }
+
+    /// Q of the LPF, HPF or tone filter. The default keeps the LPF and HPF flat.
+    fn filter_q_param(name: &str, should_update_filters: Arc<AtomicBool>) -> FloatParam {
+        FloatParam::new(
+            name,
+            FRAC_1_SQRT_2,
+            FloatRange::Skewed {
+                min: 0.5,
+                max: 10.0,
+                factor: FloatRange::skew_factor(-1.5),
+            },
+        )
+        .with_smoother(SmoothingStyle::Logarithmic(100.0))
+        .with_value_to_string(formatters::v2s_f32_rounded(2))
+        .with_callback(Arc::new(move |_| {
+            should_update_filters.store(true, Ordering::Relaxed)
+        }))
+    }
 }